@@ -34,8 +34,8 @@ use hir_ty::{
     traits::Solution,
     traits::SolutionVariables,
     ApplicationTy, BoundVar, CallableDefId, Canonical, DebruijnIndex, FnSig, GenericPredicate,
-    InEnvironment, Obligation, ProjectionPredicate, ProjectionTy, Substs, TraitEnvironment, Ty,
-    TyDefId, TyKind, TypeCtor,
+    InEnvironment, Obligation, OpaqueTy, OpaqueTyId, ProjectionPredicate, ProjectionTy, Substs,
+    TraitEnvironment, Ty, TyDefId, TyKind, TypeCtor, TypeWalk,
 };
 use rustc_hash::FxHashSet;
 use stdx::impl_from;
@@ -483,6 +483,10 @@ impl Struct {
         db.struct_data(self.id).repr.clone()
     }
 
+    pub fn kind(self, db: &dyn HirDatabase) -> StructKind {
+        self.variant_data(db).kind()
+    }
+
     fn variant_data(self, db: &dyn HirDatabase) -> Arc<VariantData> {
         db.struct_data(self.id).variant_data.clone()
     }
@@ -745,6 +749,17 @@ impl Function {
             .collect()
     }
 
+    pub fn ret_type(self, db: &dyn HirDatabase) -> Type {
+        let resolver = self.id.resolver(db.upcast());
+        let ctx = hir_ty::TyLoweringContext::new(db, &resolver);
+        let environment = TraitEnvironment::lower(db, &resolver);
+        let ret_type = &db.function_data(self.id).ret_type;
+        Type {
+            krate: self.id.lookup(db.upcast()).container.module(db.upcast()).krate,
+            ty: InEnvironment { value: Ty::from_hir_ext(&ctx, ret_type).0, environment },
+        }
+    }
+
     pub fn is_unsafe(self, db: &dyn HirDatabase) -> bool {
         db.function_data(self.id).is_unsafe
     }
@@ -1303,6 +1318,10 @@ impl Type {
         matches!(self.ty.value, Ty::Apply(ApplicationTy { ctor: TypeCtor::Bool, .. }))
     }
 
+    pub fn is_str(&self) -> bool {
+        matches!(self.ty.value, Ty::Apply(ApplicationTy { ctor: TypeCtor::Str, .. }))
+    }
+
     pub fn is_mutable_reference(&self) -> bool {
         matches!(
             self.ty.value,
@@ -1372,13 +1391,33 @@ impl Type {
         r#trait: Trait,
         args: &[Type],
         alias: TypeAlias,
-    ) -> Option<Ty> {
+    ) -> Option<Type> {
         let subst = Substs::build_for_def(db, r#trait.id)
             .push(self.ty.value.clone())
             .fill(args.iter().map(|t| t.ty.value.clone()))
             .build();
+        let ty =
+            self.solve_projection(db, ProjectionTy { associated_ty: alias.id, parameters: subst })?;
+        // The solution may still mention projections, like the
+        // `<I as Iterator>::Item` in the item of an `Enumerate<I>`.
+        let ty = ty.fold(&mut |ty| match ty {
+            Ty::Projection(projection_ty) if !has_bound_vars(&projection_ty) => self
+                .solve_projection(db, projection_ty.clone())
+                .unwrap_or(Ty::Projection(projection_ty)),
+            ty => ty,
+        });
+        return Some(self.derived(ty));
+
+        fn has_bound_vars(projection_ty: &ProjectionTy) -> bool {
+            let mut res = false;
+            projection_ty.walk(&mut |ty| res |= matches!(ty, Ty::Bound(_)));
+            res
+        }
+    }
+
+    fn solve_projection(&self, db: &dyn HirDatabase, projection_ty: ProjectionTy) -> Option<Ty> {
         let predicate = ProjectionPredicate {
-            projection_ty: ProjectionTy { associated_ty: alias.id, parameters: subst },
+            projection_ty,
             ty: Ty::Bound(BoundVar::new(DebruijnIndex::INNERMOST, 0)),
         };
         let goal = Canonical {
@@ -1419,9 +1458,10 @@ impl Type {
     }
 
     pub fn is_fn(&self) -> bool {
-        matches!(&self.ty.value,
-            Ty::Apply(ApplicationTy { ctor: TypeCtor::FnDef(..), .. }) |
-            Ty::Apply(ApplicationTy { ctor: TypeCtor::FnPtr { .. }, .. })
+        matches!(
+            &self.ty.value,
+            Ty::Apply(ApplicationTy { ctor: TypeCtor::FnDef(..), .. })
+                | Ty::Apply(ApplicationTy { ctor: TypeCtor::FnPtr { .. }, .. })
         )
     }
 
@@ -1454,6 +1494,57 @@ impl Type {
         }
     }
 
+    /// Whether this type mentions an opaque `impl Trait` type, which can't be
+    /// written down in a type annotation.
+    pub fn contains_impl_trait(&self) -> bool {
+        return go(&self.ty.value);
+
+        fn go(ty: &Ty) -> bool {
+            match ty {
+                Ty::Opaque(_) => true,
+                Ty::Apply(ApplicationTy { ctor: TypeCtor::OpaqueType(_), .. }) => true,
+                Ty::Apply(a_ty) => a_ty.parameters.iter().any(go),
+                _ => false,
+            }
+        }
+    }
+
+    /// For the opaque type returned by a `fn foo() -> impl Trait`, returns the
+    /// concrete type inferred from the body of `foo`.
+    ///
+    /// Returns `None` if the concrete type can't be written down: when it
+    /// mentions generic parameters, closures, other opaque types (including
+    /// itself, for recursive functions) or types that failed to infer.
+    pub fn impl_trait_hidden_type(&self, db: &dyn HirDatabase) -> Option<Type> {
+        let func = match &self.ty.value {
+            Ty::Opaque(OpaqueTy {
+                opaque_ty_id: OpaqueTyId::ReturnTypeImplTrait(func, 0), ..
+            }) => *func,
+            _ => return None,
+        };
+        // `async fn`s also return an `impl Future`, but their body evaluates to
+        // the future's output rather than to the future itself.
+        if Function::from(func).source(db).value.async_token().is_some() {
+            return None;
+        }
+        if !matches!(db.function_data(func).ret_type, TypeRef::ImplTrait(_)) {
+            return None;
+        }
+        let body = db.body(func.into());
+        let hidden = &db.infer(func.into())[body.body_expr];
+        return if is_nameable(hidden) { Some(self.derived(hidden.clone())) } else { None };
+
+        fn is_nameable(ty: &Ty) -> bool {
+            match ty {
+                Ty::Apply(ApplicationTy { ctor: TypeCtor::Closure { .. }, .. })
+                | Ty::Apply(ApplicationTy { ctor: TypeCtor::OpaqueType(_), .. })
+                | Ty::Apply(ApplicationTy { ctor: TypeCtor::Never, .. }) => false,
+                Ty::Apply(a_ty) => a_ty.parameters.iter().all(is_nameable),
+                _ => false,
+            }
+        }
+    }
+
     pub fn fields(&self, db: &dyn HirDatabase) -> Vec<(Field, Type)> {
         if let Ty::Apply(a_ty) = &self.ty.value {
             let variant_id = match a_ty.ctor {
@@ -1475,6 +1566,15 @@ impl Type {
         Vec::new()
     }
 
+    /// The generic arguments of an ADT, or the element types of a reference,
+    /// array, tuple and the like.
+    pub fn type_parameters(&self) -> Vec<Type> {
+        match &self.ty.value {
+            Ty::Apply(a_ty) => a_ty.parameters.iter().map(|ty| self.derived(ty.clone())).collect(),
+            _ => Vec::new(),
+        }
+    }
+
     pub fn tuple_fields(&self, _db: &dyn HirDatabase) -> Vec<Type> {
         let mut res = Vec::new();
         if let Ty::Apply(a_ty) = &self.ty.value {
@@ -1710,7 +1810,7 @@ impl Type {
 
 impl HirDisplay for Type {
     fn hir_fmt(&self, f: &mut HirFormatter) -> Result<(), HirDisplayError> {
-        self.ty.value.hir_fmt(f)
+        f.with_trait_env(self.krate, self.ty.environment.clone(), |f| self.ty.value.hir_fmt(f))
     }
 }
 
@@ -58,7 +58,7 @@ pub use hir_expand::{
     name::known, name::AsName, name::Name, HirFileId, InFile, MacroCallId, MacroCallLoc,
     /* FIXME */ MacroDefId, MacroFile, Origin,
 };
-pub use hir_ty::display::HirDisplay;
+pub use hir_ty::display::{ClosureStyle, HirDisplay};
 
 // These are negative re-exports: pub using these names is forbidden, they
 // should remain private to hir internals.
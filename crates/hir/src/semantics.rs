@@ -108,6 +108,12 @@ impl<'db, DB: HirDatabase> Semantics<'db, DB> {
         self.imp.parse(file_id)
     }
 
+    /// Makes the nodes of `file`, the tree of `file_id` parsed elsewhere,
+    /// known to these semantics, as if it came from `parse`.
+    pub fn cache_parsed(&self, file_id: FileId, file: &ast::SourceFile) {
+        self.imp.cache(file.syntax().clone(), file_id.into())
+    }
+
     pub fn expand(&self, macro_call: &ast::MacroCall) -> Option<SyntaxNode> {
         self.imp.expand(macro_call)
     }
@@ -196,10 +196,10 @@ impl ExprCollector<'_> {
                         // if let -- desugar to match
                         Some(pat) => {
                             let pat = self.collect_pat(pat);
-                            let match_expr = self.collect_expr_opt(condition.expr());
+                            let (match_expr, guard) = self.collect_let_chain(condition.expr());
                             let placeholder_pat = self.missing_pat();
                             let arms = vec![
-                                MatchArm { pat, expr: then_branch, guard: None },
+                                MatchArm { pat, expr: then_branch, guard },
                                 MatchArm {
                                     pat: placeholder_pat,
                                     expr: else_branch.unwrap_or_else(|| self.empty_block()),
@@ -269,12 +269,12 @@ impl ExprCollector<'_> {
                         Some(pat) => {
                             mark::hit!(infer_resolve_while_let);
                             let pat = self.collect_pat(pat);
-                            let match_expr = self.collect_expr_opt(condition.expr());
+                            let (match_expr, guard) = self.collect_let_chain(condition.expr());
                             let placeholder_pat = self.missing_pat();
                             let break_ =
                                 self.alloc_expr_desugared(Expr::Break { expr: None, label: None });
                             let arms = vec![
-                                MatchArm { pat, expr: body, guard: None },
+                                MatchArm { pat, expr: body, guard },
                                 MatchArm { pat: placeholder_pat, expr: break_, guard: None },
                             ];
                             let match_expr =
@@ -609,6 +609,26 @@ impl ExprCollector<'_> {
         }
     }
 
+    /// Splits the scrutinee of `if let P = a && b && c` (or `while let`) into
+    /// `a`, which is matched against `P`, and `b && c`, which guards the match,
+    /// the way a let chain reads.
+    fn collect_let_chain(&mut self, expr: Option<ast::Expr>) -> (ExprId, Option<ExprId>) {
+        let bin_expr = match expr {
+            Some(ast::Expr::BinExpr(it)) if it.op_kind() == Some(ast::BinOp::BooleanAnd) => it,
+            expr => return (self.collect_expr_opt(expr), None),
+        };
+        let (scrutinee, lhs) = self.collect_let_chain(bin_expr.lhs());
+        let rhs = self.collect_expr_opt(bin_expr.rhs());
+        let guard = match lhs {
+            Some(lhs) => {
+                let op = Some(BinaryOp::LogicOp(LogicOp::And));
+                self.alloc_expr_desugared(Expr::BinaryOp { lhs, rhs, op })
+            }
+            None => rhs,
+        };
+        (scrutinee, Some(guard))
+    }
+
     fn collect_block(&mut self, block: ast::BlockExpr) -> ExprId {
         let syntax_node_ptr = AstPtr::new(&block.clone().into());
         self.collect_block_items(&block);
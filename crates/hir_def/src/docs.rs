@@ -53,7 +53,7 @@ impl Documentation {
             AttrDefId::FieldId(it) => {
                 let src = it.parent.child_source(db);
                 match &src.value[it.local_id] {
-                    Either::Left(_tuple) => None,
+                    Either::Left(tuple) => docs_from_ast(tuple),
                     Either::Right(record) => docs_from_ast(record),
                 }
             }
@@ -1,14 +1,19 @@
 //! FIXME: write short doc here
 
-use std::fmt;
+use std::{fmt, sync::Arc};
 
 use crate::{
-    db::HirDatabase, utils::generics, ApplicationTy, CallableDefId, FnSig, GenericPredicate,
-    Obligation, OpaqueTyId, ProjectionTy, Substs, TraitRef, Ty, TypeCtor,
+    db::HirDatabase,
+    traits::{FnTrait, Solution, SolutionVariables},
+    utils::generics,
+    ApplicationTy, BoundVar, CallableDefId, Canonical, DebruijnIndex, FnSig, GenericPredicate,
+    InEnvironment, Obligation, OpaqueTyId, ProjectionPredicate, ProjectionTy, Substs,
+    TraitEnvironment, TraitRef, Ty, TyKind, TypeCtor, TypeWalk,
 };
+use base_db::CrateId;
 use hir_def::{
-    find_path, generics::TypeParamProvenance, item_scope::ItemInNs, AdtId, AssocContainerId,
-    Lookup, ModuleId,
+    find_path, generics::TypeParamProvenance, item_scope::ItemInNs, resolver::HasResolver, AdtId,
+    AssocContainerId, DefWithBodyId, HasModule, Lookup, ModuleId,
 };
 use hir_expand::name::Name;
 
@@ -19,6 +24,12 @@ pub struct HirFormatter<'a> {
     curr_size: usize,
     pub(crate) max_size: Option<usize>,
     omit_verbose_types: bool,
+    show_default_args: bool,
+    max_generic_args: Option<usize>,
+    erase_generic_args: bool,
+    normalize_projections: bool,
+    trait_env: Option<(CrateId, Arc<TraitEnvironment>)>,
+    closure_style: ClosureStyle,
     display_target: DisplayTarget,
 }
 
@@ -36,6 +47,12 @@ pub trait HirDisplay {
             t: self,
             max_size: None,
             omit_verbose_types: false,
+            show_default_args: false,
+            max_generic_args: None,
+            erase_generic_args: false,
+            normalize_projections: false,
+            trait_env: None,
+            closure_style: ClosureStyle::RANotation,
             display_target: DisplayTarget::Diagnostics,
         }
     }
@@ -55,6 +72,12 @@ pub trait HirDisplay {
             t: self,
             max_size,
             omit_verbose_types: true,
+            show_default_args: false,
+            max_generic_args: None,
+            erase_generic_args: false,
+            normalize_projections: false,
+            trait_env: None,
+            closure_style: ClosureStyle::RANotation,
             display_target: DisplayTarget::Diagnostics,
         }
     }
@@ -74,6 +97,12 @@ pub trait HirDisplay {
             curr_size: 0,
             max_size: None,
             omit_verbose_types: false,
+            show_default_args: false,
+            max_generic_args: None,
+            erase_generic_args: false,
+            normalize_projections: false,
+            trait_env: None,
+            closure_style: ClosureStyle::RANotation,
             display_target: DisplayTarget::SourceCode { module_id },
         }) {
             Ok(()) => {}
@@ -106,7 +135,9 @@ impl<'a> HirFormatter<'a> {
         // We write to a buffer first to track output size
         self.buf.clear();
         fmt::write(&mut self.buf, args)?;
-        self.curr_size += self.buf.len();
+        // The size limit is in characters, so that non-ASCII names don't use
+        // up more of it than they take on screen.
+        self.curr_size += self.buf.chars().count();
 
         // Then we write to the internal formatter from the buffer
         self.fmt.write_str(&self.buf).map_err(HirDisplayError::from)
@@ -123,6 +154,70 @@ impl<'a> HirFormatter<'a> {
     pub fn omit_verbose_types(&self) -> bool {
         self.omit_verbose_types
     }
+
+    /// Runs `f` with the crate and trait environment that projections are
+    /// normalized in.
+    pub fn with_trait_env<T>(
+        &mut self,
+        krate: CrateId,
+        environment: Arc<TraitEnvironment>,
+        f: impl FnOnce(&mut Self) -> T,
+    ) -> T {
+        let prev = self.trait_env.replace((krate, environment));
+        let res = f(self);
+        self.trait_env = prev;
+        res
+    }
+
+    /// The type `projection` normalizes to, if projections are normalized and
+    /// it is known.
+    fn normalized(&self, projection: &ProjectionTy) -> Option<Ty> {
+        if !self.normalize_projections {
+            return None;
+        }
+        let (krate, environment) = self.trait_env.clone()?;
+        let predicate = ProjectionPredicate {
+            projection_ty: projection.clone(),
+            ty: Ty::Bound(BoundVar::new(DebruijnIndex::INNERMOST, 0)),
+        };
+        if has_bound_vars(&predicate.projection_ty) {
+            return None;
+        }
+        let goal = Canonical {
+            value: InEnvironment::new(environment, Obligation::Projection(predicate)),
+            kinds: Arc::new([TyKind::General]),
+        };
+        let ty = match self.db.trait_solve(krate, goal)? {
+            Solution::Unique(SolutionVariables(subst)) => subst.value.first()?.clone(),
+            Solution::Ambig(_) => return None,
+        };
+        // Without a matching impl, the projection is solved to itself.
+        if has_bound_vars(&ty) || ty.associated_type_parent_trait(self.db).is_some() {
+            return None;
+        }
+        return Some(ty);
+
+        fn has_bound_vars(t: &impl TypeWalk) -> bool {
+            let mut res = false;
+            t.walk(&mut |ty| res |= matches!(ty, Ty::Bound(_)));
+            res
+        }
+    }
+}
+
+/// How to write the type of a closure.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ClosureStyle {
+    /// `|i32, i32| -> i32`, collapsing the parameters to `|…|` when verbose
+    /// types are omitted.
+    RANotation,
+    /// `impl Fn(i32, i32) -> i32`, naming the most permissive of the `Fn`
+    /// traits the closure implements.
+    ImplFn,
+    /// `{closure#2}`, identifying the closure by its expression.
+    ClosureWithId,
+    /// `…`, for when the type of closures isn't worth showing at all.
+    Hide,
 }
 
 #[derive(Clone, Copy)]
@@ -164,9 +259,63 @@ pub struct HirDisplayWrapper<'a, T> {
     t: &'a T,
     max_size: Option<usize>,
     omit_verbose_types: bool,
+    show_default_args: bool,
+    max_generic_args: Option<usize>,
+    erase_generic_args: bool,
+    normalize_projections: bool,
+    trait_env: Option<(CrateId, Arc<TraitEnvironment>)>,
+    closure_style: ClosureStyle,
     display_target: DisplayTarget,
 }
 
+impl<'a, T> HirDisplayWrapper<'a, T> {
+    /// Keeps writing the generic arguments that are equal to their defaults,
+    /// which are omitted along with other verbose types otherwise.
+    pub fn with_default_args(mut self, show_default_args: bool) -> Self {
+        self.show_default_args = show_default_args;
+        self
+    }
+
+    /// Writes at most `max_generic_args` generic arguments of each type,
+    /// collapsing the rest into `…`.
+    pub fn with_max_generic_args(mut self, max_generic_args: Option<usize>) -> Self {
+        self.max_generic_args = max_generic_args;
+        self
+    }
+
+    /// Writes `_` in place of the generic arguments of ADTs, as in `Vec<_>`.
+    pub fn with_erased_generic_args(mut self, erase_generic_args: bool) -> Self {
+        self.erase_generic_args = erase_generic_args;
+        self
+    }
+
+    /// Writes projections like `<Foo as Trait>::Assoc` as the type they
+    /// normalize to, where it is known. This needs the trait environment of
+    /// the type, see `HirFormatter::with_trait_env`.
+    pub fn with_normalized_projections(mut self, normalize_projections: bool) -> Self {
+        self.normalize_projections = normalize_projections;
+        self
+    }
+
+    pub fn with_closure_style(mut self, closure_style: ClosureStyle) -> Self {
+        self.closure_style = closure_style;
+        self
+    }
+
+    /// Carries the options of `f` over to the display of one of the types
+    /// `f` is writing.
+    fn with_options_of(mut self, f: &HirFormatter) -> Self {
+        self.omit_verbose_types = f.omit_verbose_types;
+        self.show_default_args = f.show_default_args;
+        self.max_generic_args = f.max_generic_args;
+        self.erase_generic_args = f.erase_generic_args;
+        self.normalize_projections = f.normalize_projections;
+        self.trait_env = f.trait_env.clone();
+        self.closure_style = f.closure_style;
+        self
+    }
+}
+
 impl<'a, T> fmt::Display for HirDisplayWrapper<'a, T>
 where
     T: HirDisplay,
@@ -179,6 +328,12 @@ where
             curr_size: 0,
             max_size: self.max_size,
             omit_verbose_types: self.omit_verbose_types,
+            show_default_args: self.show_default_args,
+            max_generic_args: self.max_generic_args,
+            erase_generic_args: self.erase_generic_args,
+            normalize_projections: self.normalize_projections,
+            trait_env: self.trait_env.clone(),
+            closure_style: self.closure_style,
             display_target: self.display_target,
         }) {
             Ok(()) => Ok(()),
@@ -213,15 +368,15 @@ impl HirDisplay for ApplicationTy {
             TypeCtor::Str => write!(f, "str")?,
             TypeCtor::Slice => {
                 let t = self.parameters.as_single();
-                write!(f, "[{}]", t.display(f.db))?;
+                write!(f, "[{}]", t.display(f.db).with_options_of(f))?;
             }
             TypeCtor::Array => {
                 let t = self.parameters.as_single();
-                write!(f, "[{}; _]", t.display(f.db))?;
+                write!(f, "[{}; _]", t.display(f.db).with_options_of(f))?;
             }
             TypeCtor::RawPtr(m) => {
                 let t = self.parameters.as_single();
-                let ty_display = t.display(f.db);
+                let ty_display = t.display(f.db).with_options_of(f);
 
                 write!(f, "*{}", m.as_keyword_for_ptr())?;
                 if matches!(t, Ty::Dyn(predicates) if predicates.len() > 1) {
@@ -235,9 +390,9 @@ impl HirDisplay for ApplicationTy {
             TypeCtor::Ref(m) => {
                 let t = self.parameters.as_single();
                 let ty_display = if f.omit_verbose_types() {
-                    t.display_truncated(f.db, f.max_size)
+                    t.display_truncated(f.db, f.max_size).with_options_of(f)
                 } else {
-                    t.display(f.db)
+                    t.display(f.db).with_options_of(f)
                 };
 
                 write!(f, "&{}", m.as_keyword_for_ref())?;
@@ -253,7 +408,7 @@ impl HirDisplay for ApplicationTy {
             TypeCtor::Tuple { .. } => {
                 let ts = &self.parameters;
                 if ts.len() == 1 {
-                    write!(f, "({},)", ts[0].display(f.db))?;
+                    write!(f, "({},)", ts[0].display(f.db).with_options_of(f))?;
                 } else {
                     write!(f, "(")?;
                     f.write_joined(&*ts.0, ", ")?;
@@ -275,9 +430,9 @@ impl HirDisplay for ApplicationTy {
                 let ret = sig.ret();
                 if *ret != Ty::unit() {
                     let ret_display = if f.omit_verbose_types() {
-                        ret.display_truncated(f.db, f.max_size)
+                        ret.display_truncated(f.db, f.max_size).with_options_of(f)
                     } else {
-                        ret.display(f.db)
+                        ret.display(f.db).with_options_of(f)
                     };
                     write!(f, " -> {}", ret_display)?;
                 }
@@ -311,9 +466,9 @@ impl HirDisplay for ApplicationTy {
                 let ret = sig.ret();
                 if *ret != Ty::unit() {
                     let ret_display = if f.omit_verbose_types() {
-                        ret.display_truncated(f.db, f.max_size)
+                        ret.display_truncated(f.db, f.max_size).with_options_of(f)
                     } else {
-                        ret.display(f.db)
+                        ret.display(f.db).with_options_of(f)
                     };
                     write!(f, " -> {}", ret_display)?;
                 }
@@ -344,41 +499,55 @@ impl HirDisplay for ApplicationTy {
                 }
 
                 if self.parameters.len() > 0 {
-                    let parameters_to_write =
-                        if f.display_target.is_source_code() || f.omit_verbose_types() {
-                            match self
-                                .ctor
-                                .as_generic_def()
-                                .map(|generic_def_id| f.db.generic_defaults(generic_def_id))
-                                .filter(|defaults| !defaults.is_empty())
-                            {
-                                None => self.parameters.0.as_ref(),
-                                Some(default_parameters) => {
-                                    let mut default_from = 0;
-                                    for (i, parameter) in self.parameters.iter().enumerate() {
-                                        match (parameter, default_parameters.get(i)) {
-                                            (&Ty::Unknown, _) | (_, None) => {
+                    let parameters_to_write = if f.display_target.is_source_code()
+                        || (f.omit_verbose_types() && !f.show_default_args)
+                    {
+                        match self
+                            .ctor
+                            .as_generic_def()
+                            .map(|generic_def_id| f.db.generic_defaults(generic_def_id))
+                            .filter(|defaults| !defaults.is_empty())
+                        {
+                            None => self.parameters.0.as_ref(),
+                            Some(default_parameters) => {
+                                let mut default_from = 0;
+                                for (i, parameter) in self.parameters.iter().enumerate() {
+                                    match (parameter, default_parameters.get(i)) {
+                                        (&Ty::Unknown, _) | (_, None) => {
+                                            default_from = i + 1;
+                                        }
+                                        (_, Some(default_parameter)) => {
+                                            let actual_default = default_parameter
+                                                .clone()
+                                                .subst(&self.parameters.prefix(i));
+                                            if parameter != &actual_default {
                                                 default_from = i + 1;
                                             }
-                                            (_, Some(default_parameter)) => {
-                                                let actual_default = default_parameter
-                                                    .clone()
-                                                    .subst(&self.parameters.prefix(i));
-                                                if parameter != &actual_default {
-                                                    default_from = i + 1;
-                                                }
-                                            }
                                         }
                                     }
-                                    &self.parameters.0[0..default_from]
                                 }
+                                &self.parameters.0[0..default_from]
                             }
-                        } else {
-                            self.parameters.0.as_ref()
-                        };
+                        }
+                    } else {
+                        self.parameters.0.as_ref()
+                    };
                     if !parameters_to_write.is_empty() {
                         write!(f, "<")?;
-                        f.write_joined(parameters_to_write, ", ")?;
+                        match f.max_generic_args {
+                            _ if f.erase_generic_args => {
+                                let erased = vec!["_"; parameters_to_write.len()];
+                                write!(f, "{}", erased.join(", "))?;
+                            }
+                            Some(max) if parameters_to_write.len() > max => {
+                                for parameter in &parameters_to_write[..max] {
+                                    parameter.hir_fmt(f)?;
+                                    write!(f, ", ")?;
+                                }
+                                write!(f, "{}", TYPE_HINT_TRUNCATION)?;
+                            }
+                            _ => f.write_joined(parameters_to_write, ", ")?,
+                        }
                         write!(f, ">")?;
                     }
                 }
@@ -390,6 +559,14 @@ impl HirDisplay for ApplicationTy {
                 };
                 let trait_ = f.db.trait_data(trait_);
                 let type_alias = f.db.type_alias_data(type_alias);
+                // Hints spell projections on type parameters like the source
+                // does, as in `I::Item`.
+                if f.omit_verbose_types() && self.parameters.len() == 1 {
+                    if let Ty::Placeholder(_) = &self.parameters[0] {
+                        self.parameters[0].hir_fmt(f)?;
+                        return write!(f, "::{}", type_alias.name);
+                    }
+                }
                 write!(f, "{}::{}", trait_.name, type_alias.name)?;
                 if self.parameters.len() > 0 {
                     write!(f, "<")?;
@@ -426,8 +603,30 @@ impl HirDisplay for ApplicationTy {
                     }
                 }
             }
-            TypeCtor::Closure { .. } => {
+            TypeCtor::Closure { def, expr } => {
                 let sig = self.parameters[0].callable_sig(f.db);
+                match f.closure_style {
+                    ClosureStyle::RANotation => {}
+                    ClosureStyle::ClosureWithId => {
+                        return write!(f, "{{closure#{}}}", u32::from(expr.into_raw()));
+                    }
+                    ClosureStyle::Hide => return write!(f, "{}", TYPE_HINT_TRUNCATION),
+                    ClosureStyle::ImplFn => {
+                        let sig = match sig {
+                            Some(it) => it,
+                            None => return write!(f, "{{closure}}"),
+                        };
+                        let fn_trait = closure_fn_trait(f.db, def, self, &sig);
+                        write!(f, "impl {:?}(", fn_trait)?;
+                        f.write_joined(sig.params(), ", ")?;
+                        write!(f, ")")?;
+                        if *sig.ret() != Ty::unit() {
+                            write!(f, " -> ")?;
+                            sig.ret().hir_fmt(f)?;
+                        }
+                        return Ok(());
+                    }
+                }
                 if let Some(sig) = sig {
                     if sig.params().is_empty() {
                         write!(f, "||")?;
@@ -440,9 +639,9 @@ impl HirDisplay for ApplicationTy {
                     };
 
                     let ret_display = if f.omit_verbose_types() {
-                        sig.ret().display_truncated(f.db, f.max_size)
+                        sig.ret().display_truncated(f.db, f.max_size).with_options_of(f)
                     } else {
-                        sig.ret().display(f.db)
+                        sig.ret().display(f.db).with_options_of(f)
                     };
                     write!(f, " -> {}", ret_display)?;
                 } else {
@@ -454,11 +653,55 @@ impl HirDisplay for ApplicationTy {
     }
 }
 
+/// The most permissive of `Fn`, `FnMut` and `FnOnce` that `closure`
+/// implements. Every closure is at least `FnOnce`, even where the trait
+/// itself isn't declared.
+fn closure_fn_trait(
+    db: &dyn HirDatabase,
+    def: DefWithBodyId,
+    closure: &ApplicationTy,
+    sig: &FnSig,
+) -> FnTrait {
+    let args = Ty::apply(
+        TypeCtor::Tuple { cardinality: sig.params().len() as u16 },
+        Substs(sig.params().into()),
+    );
+    let krate = def.module(db.upcast()).krate;
+    let environment = TraitEnvironment::lower(db, &def.resolver(db.upcast()));
+    let closure = Ty::Apply(closure.clone());
+    let implements = |fn_trait: FnTrait| {
+        let trait_ = match fn_trait.get_id(db, krate) {
+            Some(it) => it,
+            None => return false,
+        };
+        let trait_ref = TraitRef {
+            trait_,
+            substs: Substs::build_for_def(db, trait_)
+                .push(closure.clone())
+                .push(args.clone())
+                .build(),
+        };
+        let goal = Canonical {
+            value: InEnvironment::new(environment.clone(), Obligation::Trait(trait_ref)),
+            kinds: Arc::new([]),
+        };
+        db.trait_solve(krate, goal).is_some()
+    };
+    [FnTrait::Fn, FnTrait::FnMut]
+        .iter()
+        .copied()
+        .find(|&it| implements(it))
+        .unwrap_or(FnTrait::FnOnce)
+}
+
 impl HirDisplay for ProjectionTy {
     fn hir_fmt(&self, f: &mut HirFormatter) -> Result<(), HirDisplayError> {
         if f.should_truncate() {
             return write!(f, "{}", TYPE_HINT_TRUNCATION);
         }
+        if let Some(ty) = f.normalized(self) {
+            return ty.hir_fmt(f);
+        }
 
         let trait_ = f.db.trait_data(self.trait_(f.db));
         write!(f, "<{} as {}", self.parameters[0].display(f.db), trait_.name)?;
@@ -499,7 +499,7 @@ impl Ty {
             TyDefId::AdtId(it) => Some(it.into()),
             TyDefId::TypeAliasId(it) => Some(it.into()),
         };
-        let substs = substs_from_path_segment(ctx, segment, generic_def, infer_args);
+        let substs = substs_from_path_segment(ctx, segment, generic_def, infer_args, None);
         ctx.db.ty(typable).subst(&substs)
     }
 
@@ -536,7 +536,7 @@ impl Ty {
                 (segment, Some(var.parent.into()))
             }
         };
-        substs_from_path_segment(ctx, segment, generic_def, infer_args)
+        substs_from_path_segment(ctx, segment, generic_def, infer_args, None)
     }
 }
 
@@ -545,6 +545,7 @@ fn substs_from_path_segment(
     segment: PathSegment<'_>,
     def_generic: Option<GenericDefId>,
     infer_args: bool,
+    explicit_self_ty: Option<Ty>,
 ) -> Substs {
     let mut substs = Vec::new();
     let def_generics = def_generic.map(|def| generics(ctx.db.upcast(), def));
@@ -555,11 +556,14 @@ fn substs_from_path_segment(
 
     substs.extend(iter::repeat(Ty::Unknown).take(parent_params));
 
+    // The self type goes in before the defaults, which may refer to it, as
+    // the `Rhs = Self` of `Add`.
+    let self_ty = explicit_self_ty.unwrap_or(Ty::Unknown);
     let mut had_explicit_args = false;
 
     if let Some(generic_args) = &segment.args_and_bindings {
         if !generic_args.has_self_type {
-            substs.extend(iter::repeat(Ty::Unknown).take(self_params));
+            substs.extend(iter::repeat(self_ty).take(self_params));
         }
         let expected_num =
             if generic_args.has_self_type { self_params + type_params } else { type_params };
@@ -574,6 +578,8 @@ fn substs_from_path_segment(
                 }
             }
         }
+    } else {
+        substs.extend(iter::repeat(self_ty).take(self_params));
     }
 
     // handle defaults. In expression or pattern path segments without
@@ -623,7 +629,8 @@ impl TraitRef {
         segment: PathSegment<'_>,
         explicit_self_ty: Option<Ty>,
     ) -> Self {
-        let mut substs = TraitRef::substs_from_path(ctx, segment, resolved);
+        let mut substs =
+            TraitRef::substs_from_path(ctx, segment, resolved, explicit_self_ty.clone());
         if let Some(self_ty) = explicit_self_ty {
             make_mut_slice(&mut substs.0)[0] = self_ty;
         }
@@ -646,8 +653,9 @@ impl TraitRef {
         ctx: &TyLoweringContext<'_>,
         segment: PathSegment<'_>,
         resolved: TraitId,
+        explicit_self_ty: Option<Ty>,
     ) -> Substs {
-        substs_from_path_segment(ctx, segment, Some(resolved.into()), false)
+        substs_from_path_segment(ctx, segment, Some(resolved.into()), false, explicit_self_ty)
     }
 
     pub(crate) fn from_type_bound(
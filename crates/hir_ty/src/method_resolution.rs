@@ -29,6 +29,7 @@ use crate::{
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum TyFingerprint {
     Apply(TypeCtor),
+    Dyn(TraitId),
 }
 
 impl TyFingerprint {
@@ -38,6 +39,7 @@ impl TyFingerprint {
     pub(crate) fn for_impl(ty: &Ty) -> Option<TyFingerprint> {
         match ty {
             Ty::Apply(a_ty) => Some(TyFingerprint::Apply(a_ty.ctor)),
+            Ty::Dyn(_) => ty.dyn_trait().map(TyFingerprint::Dyn),
             _ => None,
         }
     }
@@ -272,6 +274,12 @@ impl Ty {
                 TypeCtor::RawPtr(Mutability::Mut) => lang_item_crate!("mut_ptr"),
                 _ => return None,
             },
+            Ty::Dyn(_) => {
+                return self.dyn_trait().map(|trait_| {
+                    std::iter::once(trait_.lookup(db.upcast()).container.module(db.upcast()).krate)
+                        .collect()
+                })
+            }
             _ => return None,
         };
         let res = lang_item_targets
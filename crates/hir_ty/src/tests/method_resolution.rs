@@ -1052,6 +1052,31 @@ fn dyn_trait_super_trait_not_in_scope() {
     );
 }
 
+#[test]
+fn inherent_method_on_dyn_trait() {
+    check_types(
+        r#"
+//- /main.rs crate:main deps:other_crate
+use other_crate::Any;
+
+struct S;
+
+fn test(any: &dyn Any) {
+    let s = any.downcast_ref::<S>();
+    s;
+} //^ Option<&S>
+
+//- /lib.rs crate:other_crate
+pub enum Option<T> { None, Some(T) }
+pub trait Any {}
+impl<T> Any for T {}
+impl dyn Any {
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> { loop {} }
+}
+"#,
+    );
+}
+
 #[test]
 fn method_resolution_foreign_opaque_type() {
     check_infer(
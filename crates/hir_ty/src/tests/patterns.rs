@@ -580,6 +580,68 @@ fn main() {
     )
 }
 
+#[test]
+fn infer_if_let_chain() {
+    check_infer_with_mismatches(
+        r#"
+enum Option<T> { None, Some(T) }
+use Option::Some;
+
+fn test(opt: Option<u32>, flag: bool) {
+    if let Some(x) = opt && flag && x > 1 {
+        x;
+    }
+}
+        "#,
+        expect![[r#"
+            60..63 'opt': Option<u32>
+            78..82 'flag': bool
+            90..154 '{     ...   } }': ()
+            96..152 'if let...     }': ()
+            103..110 'Some(x)': Option<u32>
+            108..109 'x': u32
+            113..116 'opt': Option<u32>
+            120..124 'flag': bool
+            128..129 'x': u32
+            128..133 'x > 1': bool
+            132..133 '1': u32
+            134..152 '{     ...     }': ()
+            144..145 'x': u32
+        "#]],
+    )
+}
+
+#[test]
+fn infer_while_let_chain() {
+    check_infer_with_mismatches(
+        r#"
+enum Option<T> { None, Some(T) }
+use Option::Some;
+
+fn test(opt: Option<u32>, flag: bool) {
+    while let Some(x) = opt && flag && x > 1 {
+        x;
+    }
+}
+        "#,
+        expect![[r#"
+            60..63 'opt': Option<u32>
+            78..82 'flag': bool
+            90..157 '{     ...   } }': ()
+            96..155 'while ...     }': ()
+            106..113 'Some(x)': Option<u32>
+            111..112 'x': u32
+            116..119 'opt': Option<u32>
+            123..127 'flag': bool
+            131..132 'x': u32
+            131..136 'x > 1': bool
+            135..136 '1': u32
+            137..155 '{     ...     }': ()
+            147..148 'x': u32
+        "#]],
+    )
+}
+
 #[test]
 fn match_ergonomics_in_closure_params() {
     check_infer(
@@ -3151,3 +3151,27 @@ fn test() {
     "#,
     );
 }
+
+#[test]
+fn impl_trait_ref_default_refers_to_self() {
+    check_types(
+        r#"
+trait Add<Rhs = Self> {
+    type Output;
+}
+
+struct Matrix;
+struct Vector;
+impl Add for Matrix {
+    type Output = Vector;
+}
+
+fn add<A: Add<B>, B>(a: A, b: B) -> A::Output { loop {} }
+
+fn test() {
+    add(Matrix, Matrix);
+  //^^^^^^^^^^^^^^^^^^^ Vector
+}
+"#,
+    );
+}
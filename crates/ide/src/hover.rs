@@ -16,9 +16,8 @@ use crate::{
     display::{macro_label, ShortLabel, ToNav, TryToNav},
     link_rewrite::{remove_links, rewrite_links},
     markdown_remove::remove_markdown,
-    markup::Markup,
     runnables::runnable,
-    FileId, FilePosition, NavigationTarget, RangeInfo, Runnable,
+    FileId, FilePosition, Markup, NavigationTarget, RangeInfo, Runnable,
 };
 
 #[derive(Clone, Debug, PartialEq, Eq)]
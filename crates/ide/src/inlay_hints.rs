@@ -1,44 +1,3 @@
-use assists::utils::FamousDefs;
-use hir::{known, HirDisplay, Semantics};
-use ide_db::RootDatabase;
-use stdx::to_lower_snake_case;
-use syntax::{
-    ast::{self, ArgListOwner, AstNode},
-    match_ast, Direction, NodeOrToken, SmolStr, SyntaxKind, TextRange, T,
-};
-
-use crate::FileId;
-use ast::NameOwner;
-use either::Either;
-
-#[derive(Clone, Debug, PartialEq, Eq)]
-pub struct InlayHintsConfig {
-    pub type_hints: bool,
-    pub parameter_hints: bool,
-    pub chaining_hints: bool,
-    pub max_length: Option<usize>,
-}
-
-impl Default for InlayHintsConfig {
-    fn default() -> Self {
-        Self { type_hints: true, parameter_hints: true, chaining_hints: true, max_length: None }
-    }
-}
-
-#[derive(Clone, Debug, PartialEq, Eq)]
-pub enum InlayKind {
-    TypeHint,
-    ParameterHint,
-    ChainingHint,
-}
-
-#[derive(Debug)]
-pub struct InlayHint {
-    pub range: TextRange,
-    pub kind: InlayKind,
-    pub label: SmolStr,
-}
-
 // Feature: Inlay Hints
 //
 // rust-analyzer shows additional information inline with the source code.
@@ -59,342 +18,83 @@ pub struct InlayHint {
 //
 // | VS Code | **Rust Analyzer: Toggle inlay hints*
 // |===
-pub(crate) fn inlay_hints(
-    db: &RootDatabase,
-    file_id: FileId,
-    config: &InlayHintsConfig,
-) -> Vec<InlayHint> {
-    let _p = profile::span("inlay_hints");
-    let sema = Semantics::new(db);
-    let file = sema.parse(file_id);
-
-    let mut res = Vec::new();
-    for node in file.syntax().descendants() {
-        if let Some(expr) = ast::Expr::cast(node.clone()) {
-            get_chaining_hints(&mut res, &sema, config, expr);
-        }
-
-        match_ast! {
-            match node {
-                ast::CallExpr(it) => { get_param_name_hints(&mut res, &sema, config, ast::Expr::from(it)); },
-                ast::MethodCallExpr(it) => { get_param_name_hints(&mut res, &sema, config, ast::Expr::from(it)); },
-                ast::IdentPat(it) => { get_bind_pat_hints(&mut res, &sema, config, it); },
-                _ => (),
-            }
-        }
-    }
-    res
-}
-
-fn get_chaining_hints(
-    acc: &mut Vec<InlayHint>,
-    sema: &Semantics<RootDatabase>,
-    config: &InlayHintsConfig,
-    expr: ast::Expr,
-) -> Option<()> {
-    if !config.chaining_hints {
-        return None;
-    }
-
-    if matches!(expr, ast::Expr::RecordExpr(_)) {
-        return None;
-    }
-
-    let mut tokens = expr
-        .syntax()
-        .siblings_with_tokens(Direction::Next)
-        .filter_map(NodeOrToken::into_token)
-        .filter(|t| match t.kind() {
-            SyntaxKind::WHITESPACE if !t.text().contains('\n') => false,
-            SyntaxKind::COMMENT => false,
-            _ => true,
-        });
-
-    // Chaining can be defined as an expression whose next sibling tokens are newline and dot
-    // Ignoring extra whitespace and comments
-    let next = tokens.next()?.kind();
-    let next_next = tokens.next()?.kind();
-    if next == SyntaxKind::WHITESPACE && next_next == T![.] {
-        let ty = sema.type_of_expr(&expr)?;
-        if ty.is_unknown() {
-            return None;
-        }
-        if matches!(expr, ast::Expr::PathExpr(_)) {
-            if let Some(hir::Adt::Struct(st)) = ty.as_adt() {
-                if st.fields(sema.db).is_empty() {
-                    return None;
-                }
-            }
-        }
-        acc.push(InlayHint {
-            range: expr.syntax().text_range(),
-            kind: InlayKind::ChainingHint,
-            label: hint_iterator(sema, config, &ty).unwrap_or_else(|| {
-                ty.display_truncated(sema.db, config.max_length).to_string().into()
-            }),
-        });
-    }
-    Some(())
-}
-
-fn get_param_name_hints(
-    acc: &mut Vec<InlayHint>,
-    sema: &Semantics<RootDatabase>,
-    config: &InlayHintsConfig,
-    expr: ast::Expr,
-) -> Option<()> {
-    if !config.parameter_hints {
-        return None;
-    }
-
-    let args = match &expr {
-        ast::Expr::CallExpr(expr) => expr.arg_list()?.args(),
-        ast::Expr::MethodCallExpr(expr) => expr.arg_list()?.args(),
-        _ => return None,
-    };
-
-    let callable = get_callable(sema, &expr)?;
-    let hints = callable
-        .params(sema.db)
-        .into_iter()
-        .zip(args)
-        .filter_map(|((param, _ty), arg)| {
-            let param_name = match param? {
-                Either::Left(self_param) => self_param.to_string(),
-                Either::Right(pat) => match pat {
-                    ast::Pat::IdentPat(it) => it.name()?.to_string(),
-                    _ => return None,
-                },
-            };
-            Some((param_name, arg))
-        })
-        .filter(|(param_name, arg)| should_show_param_name_hint(sema, &callable, &param_name, &arg))
-        .map(|(param_name, arg)| InlayHint {
-            range: arg.syntax().text_range(),
-            kind: InlayKind::ParameterHint,
-            label: param_name.into(),
-        });
-
-    acc.extend(hints);
-    Some(())
-}
-
-fn get_bind_pat_hints(
-    acc: &mut Vec<InlayHint>,
-    sema: &Semantics<RootDatabase>,
-    config: &InlayHintsConfig,
-    pat: ast::IdentPat,
-) -> Option<()> {
-    if !config.type_hints {
-        return None;
-    }
-
-    let ty = sema.type_of_pat(&pat.clone().into())?;
-
-    if should_not_display_type_hint(sema, &pat, &ty) {
-        return None;
-    }
-    acc.push(InlayHint {
-        range: pat.syntax().text_range(),
-        kind: InlayKind::TypeHint,
-        label: hint_iterator(sema, config, &ty)
-            .unwrap_or_else(|| ty.display_truncated(sema.db, config.max_length).to_string().into()),
-    });
-
-    Some(())
-}
-
-/// Checks if the type is an Iterator from std::iter and replaces its hint with an `impl Iterator<Item = Ty>`.
-fn hint_iterator(
-    sema: &Semantics<RootDatabase>,
-    config: &InlayHintsConfig,
-    ty: &hir::Type,
-) -> Option<SmolStr> {
-    let db = sema.db;
-    let strukt = std::iter::successors(Some(ty.clone()), |ty| ty.remove_ref())
-        .last()
-        .and_then(|strukt| strukt.as_adt())?;
-    let krate = strukt.krate(db)?;
-    if krate.declaration_name(db).as_deref() != Some("core") {
-        return None;
-    }
-    let iter_trait = FamousDefs(sema, krate).core_iter_Iterator()?;
-    let iter_mod = FamousDefs(sema, krate).core_iter()?;
-    // assert this type comes from `core::iter`
-    iter_mod.visibility_of(db, &iter_trait.into()).filter(|&vis| vis == hir::Visibility::Public)?;
-    if ty.impls_trait(db, iter_trait, &[]) {
-        let assoc_type_item = iter_trait.items(db).into_iter().find_map(|item| match item {
-            hir::AssocItem::TypeAlias(alias) if alias.name(db) == known::Item => Some(alias),
-            _ => None,
-        })?;
-        if let Some(ty) = ty.normalize_trait_assoc_type(db, iter_trait, &[], assoc_type_item) {
-            const LABEL_START: &str = "impl Iterator<Item = ";
-            const LABEL_END: &str = ">";
-
-            let ty_display = ty.display_truncated(
-                db,
-                config
-                    .max_length
-                    .map(|len| len.saturating_sub(LABEL_START.len() + LABEL_END.len())),
-            );
-            return Some(format!("{}{}{}", LABEL_START, ty_display, LABEL_END).into());
-        }
-    }
-
-    None
-}
-
-fn pat_is_enum_variant(db: &RootDatabase, bind_pat: &ast::IdentPat, pat_ty: &hir::Type) -> bool {
-    if let Some(hir::Adt::Enum(enum_data)) = pat_ty.as_adt() {
-        let pat_text = bind_pat.to_string();
-        enum_data
-            .variants(db)
-            .into_iter()
-            .map(|variant| variant.name(db).to_string())
-            .any(|enum_name| enum_name == pat_text)
-    } else {
-        false
-    }
-}
-
-fn should_not_display_type_hint(
-    sema: &Semantics<RootDatabase>,
-    bind_pat: &ast::IdentPat,
-    pat_ty: &hir::Type,
-) -> bool {
-    let db = sema.db;
-
-    if pat_ty.is_unknown() {
-        return true;
-    }
+pub(crate) use ide_db::inlay_hints::{
+    inlay_hint_at, inlay_hint_for_expr_at, inlay_hints, inlay_hints_compact, inlay_hints_count,
+    inlay_hints_range, resolve_inlay_hint,
+};
 
-    if let Some(hir::Adt::Struct(s)) = pat_ty.as_adt() {
-        if s.fields(db).is_empty() && s.name(db).to_string() == bind_pat.to_string() {
-            return true;
-        }
-    }
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
 
-    for node in bind_pat.syntax().ancestors() {
-        match_ast! {
-            match node {
-                ast::LetStmt(it) => {
-                    return it.ty().is_some()
-                },
-                ast::Param(it) => {
-                    return it.ty().is_some()
-                },
-                ast::MatchArm(_it) => {
-                    return pat_is_enum_variant(db, bind_pat, pat_ty);
-                },
-                ast::IfExpr(it) => {
-                    return it.condition().and_then(|condition| condition.pat()).is_some()
-                        && pat_is_enum_variant(db, bind_pat, pat_ty);
-                },
-                ast::WhileExpr(it) => {
-                    return it.condition().and_then(|condition| condition.pat()).is_some()
-                        && pat_is_enum_variant(db, bind_pat, pat_ty);
-                },
-                ast::ForExpr(it) => {
-                    // We *should* display hint only if user provided "in {expr}" and we know the type of expr (and it's not unit).
-                    // Type of expr should be iterable.
-                    return it.in_token().is_none() ||
-                        it.iterable()
-                            .and_then(|iterable_expr|sema.type_of_expr(&iterable_expr))
-                            .map(|iterable_ty| iterable_ty.is_unknown() || iterable_ty.is_unit())
-                            .unwrap_or(true)
-                },
-                _ => (),
-            }
-        }
-    }
-    false
-}
-
-fn should_show_param_name_hint(
-    sema: &Semantics<RootDatabase>,
-    callable: &hir::Callable,
-    param_name: &str,
-    argument: &ast::Expr,
-) -> bool {
-    let param_name = param_name.trim_start_matches('_');
-    let fn_name = match callable.kind() {
-        hir::CallableKind::Function(it) => Some(it.name(sema.db).to_string()),
-        hir::CallableKind::TupleStruct(_)
-        | hir::CallableKind::TupleEnumVariant(_)
-        | hir::CallableKind::Closure => None,
+    use base_db::{
+        fixture::WithFixture, CrateGraph, CrateName, Edition, Env, FileSet, SourceRoot, VfsPath,
     };
-    if param_name.is_empty()
-        || Some(param_name) == fn_name.as_ref().map(|s| s.trim_start_matches('_'))
-        || is_argument_similar_to_param_name(sema, argument, param_name)
-        || param_name.starts_with("ra_fixture")
-    {
-        return false;
-    }
-
-    // avoid displaying hints for common functions like map, filter, etc.
-    // or other obvious words used in std
-    !(callable.n_params() == 1 && is_obvious_param(param_name))
-}
-
-fn is_argument_similar_to_param_name(
-    sema: &Semantics<RootDatabase>,
-    argument: &ast::Expr,
-    param_name: &str,
-) -> bool {
-    if is_enum_name_similar_to_param_name(sema, argument, param_name) {
-        return true;
-    }
-    match get_string_representation(argument) {
-        None => false,
-        Some(repr) => {
-            let argument_string = repr.trim_start_matches('_');
-            argument_string.starts_with(param_name) || argument_string.ends_with(param_name)
-        }
-    }
-}
-
-fn is_enum_name_similar_to_param_name(
-    sema: &Semantics<RootDatabase>,
-    argument: &ast::Expr,
-    param_name: &str,
-) -> bool {
-    match sema.type_of_expr(argument).and_then(|t| t.as_adt()) {
-        Some(hir::Adt::Enum(e)) => to_lower_snake_case(&e.name(sema.db).to_string()) == param_name,
-        _ => false,
-    }
-}
-
-fn get_string_representation(expr: &ast::Expr) -> Option<String> {
-    match expr {
-        ast::Expr::MethodCallExpr(method_call_expr) => {
-            Some(method_call_expr.name_ref()?.to_string())
-        }
-        ast::Expr::RefExpr(ref_expr) => get_string_representation(&ref_expr.expr()?),
-        _ => Some(expr.to_string()),
-    }
-}
+    use cfg::CfgOptions;
+    use expect_test::{expect, Expect};
+    use hir::ClosureStyle;
+    use rustc_hash::FxHashSet;
+    use test_utils::extract_annotations;
 
-fn is_obvious_param(param_name: &str) -> bool {
-    let is_obvious_param_name =
-        matches!(param_name, "predicate" | "value" | "pat" | "rhs" | "other");
-    param_name.len() == 1 || is_obvious_param_name
-}
+    use syntax::{AstNode, SyntaxNodePtr, TextRange, TextSize};
 
-fn get_callable(sema: &Semantics<RootDatabase>, expr: &ast::Expr) -> Option<hir::Callable> {
-    match expr {
-        ast::Expr::CallExpr(expr) => sema.type_of_expr(&expr.expr()?)?.as_callable(sema.db),
-        ast::Expr::MethodCallExpr(expr) => sema.resolve_method_call_as_callable(expr),
-        _ => None,
-    }
-}
+    use ide_db::{
+        helpers::FamousDefs,
+        inlay_hints::{inlay_hints_in_file, InlayHintsDatabase},
+        RootDatabase,
+    };
 
-#[cfg(test)]
-mod tests {
-    use assists::utils::FamousDefs;
-    use expect_test::{expect, Expect};
-    use test_utils::extract_annotations;
+    use crate::{
+        fixture, AnalysisHost, ChainingHintsAnchor, Change, FileId, FilePosition,
+        HintRenderTemplates, HintTemplate, InlayHint, InlayHintsConfig, InlayKind, TextEdit,
+        TruncationPolicy,
+    };
 
-    use crate::{fixture, inlay_hints::InlayHintsConfig};
+    const DISABLED_CONFIG: InlayHintsConfig = InlayHintsConfig {
+        type_hints: false,
+        parameter_hints: false,
+        self_param_hints: false,
+        self_param_hints_only_when_ufcs: false,
+        tuple_constructor_hints: false,
+        chaining_hints: false,
+        question_mark_chain_hints: false,
+        dedup_adjacent_chaining_hints: false,
+        chaining_hints_on_change_only: false,
+        chaining_hints_on_record_literals: false,
+        chaining_hints_anchor: ChainingHintsAnchor::Receiver,
+        question_mark_conversion_hints: false,
+        index_output_hints: false,
+        operator_hints: false,
+        deref_hints: false,
+        arg_list_type_hints: false,
+        literal_type_hints: false,
+        inferred_constructor_type_hints: false,
+        combine_colocated_hints: false,
+        surprise_inference_hints: false,
+        adjustment_hints: false,
+        arg_order_mismatch_hints: false,
+        closure_capture_hints: false,
+        tuple_struct_field_doc_hints: false,
+        show_lifetimes_in_hints: false,
+        show_default_generic_args: false,
+        result_shortening: false,
+        hide_string_type_hints: false,
+        hide_hints_for_obvious_constructors: false,
+        hide_hints_when_type_in_rhs: false,
+        expand_impl_trait_hints: false,
+        hint_function_filter: None,
+        max_length: None,
+        truncation_policy: TruncationPolicy::Chars,
+        collapse_dyn_bounds: false,
+        max_generic_args: None,
+        minimal_type_hints: false,
+        normalize_projections: false,
+        closure_style: ClosureStyle::RANotation,
+        lazy_labels: false,
+        hint_tooltips: false,
+        hint_ids: false,
+        render_templates: HintRenderTemplates::EMPTY,
+    };
 
     fn check(ra_fixture: &str) {
         check_with_config(InlayHintsConfig::default(), ra_fixture);
@@ -411,6 +111,32 @@ mod tests {
         assert_eq!(expected, actual, "\nExpected:\n{:#?}\n\nActual:\n{:#?}", expected, actual);
     }
 
+    /// Like `check_with_config`, for annotations that start with the kind of
+    /// the hint, as in `//^ type: i32`.
+    fn check_with_kinds(config: InlayHintsConfig, ra_fixture: &str) {
+        let ra_fixture =
+            format!("//- /main.rs crate:main deps:core\n{}\n{}", ra_fixture, FamousDefs::FIXTURE);
+        let (analysis, file_id) = fixture::file(&ra_fixture);
+        let expected = extract_annotations(&analysis.file_text(file_id).unwrap());
+        let inlay_hints = analysis.inlay_hints(file_id, &config).unwrap();
+        let actual = inlay_hints
+            .into_iter()
+            .map(|it| {
+                let kind = match it.kind {
+                    InlayKind::TypeHint => "type",
+                    InlayKind::ParameterHint => "param",
+                    InlayKind::ChainingHint => "chaining",
+                    InlayKind::SurpriseInferenceHint => "surprise",
+                    InlayKind::AdjustmentHint => "adjustment",
+                    InlayKind::ArgOrderMismatchHint => "arg_order",
+                    InlayKind::ClosureCaptureHint => "capture",
+                };
+                (it.range, format!("{}: {}", kind, it.label))
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(expected, actual, "\nExpected:\n{:#?}\n\nActual:\n{:#?}", expected, actual);
+    }
+
     fn check_expect(config: InlayHintsConfig, ra_fixture: &str, expect: Expect) {
         let ra_fixture =
             format!("//- /main.rs crate:main deps:core\n{}\n{}", ra_fixture, FamousDefs::FIXTURE);
@@ -419,15 +145,19 @@ mod tests {
         expect.assert_debug_eq(&inlay_hints)
     }
 
+    fn check_range(config: InlayHintsConfig, ra_fixture: &str, expect: Expect) {
+        let ra_fixture =
+            format!("//- /main.rs crate:main deps:core\n{}\n{}", ra_fixture, FamousDefs::FIXTURE);
+        let (analysis, frange) = fixture::range(&ra_fixture);
+        let inlay_hints =
+            analysis.inlay_hints_range(frange.file_id, Some(frange.range), &config).unwrap();
+        expect.assert_debug_eq(&inlay_hints)
+    }
+
     #[test]
     fn param_hints_only() {
         check_with_config(
-            InlayHintsConfig {
-                parameter_hints: true,
-                type_hints: false,
-                chaining_hints: false,
-                max_length: None,
-            },
+            InlayHintsConfig { parameter_hints: true, ..DISABLED_CONFIG },
             r#"
 fn foo(a: i32, b: i32) -> i32 { a + b }
 fn main() {
@@ -444,12 +174,7 @@ fn main() {
     #[test]
     fn hints_disabled() {
         check_with_config(
-            InlayHintsConfig {
-                type_hints: false,
-                parameter_hints: false,
-                chaining_hints: false,
-                max_length: None,
-            },
+            DISABLED_CONFIG,
             r#"
 fn foo(a: i32, b: i32) -> i32 { a + b }
 fn main() {
@@ -459,14 +184,180 @@ fn main() {
     }
 
     #[test]
-    fn type_hints_only() {
+    fn hint_kinds_enabled_one_at_a_time() {
+        assert!(!DISABLED_CONFIG.any_kind_enabled());
+        assert!(!InlayHintsConfig {
+            self_param_hints: true,
+            tuple_constructor_hints: true,
+            tuple_struct_field_doc_hints: true,
+            show_lifetimes_in_hints: true,
+            ..DISABLED_CONFIG
+        }
+        .any_kind_enabled());
+        for config in &[
+            InlayHintsConfig { type_hints: true, ..DISABLED_CONFIG },
+            InlayHintsConfig { parameter_hints: true, ..DISABLED_CONFIG },
+            InlayHintsConfig { chaining_hints: true, ..DISABLED_CONFIG },
+            InlayHintsConfig { question_mark_chain_hints: true, ..DISABLED_CONFIG },
+            InlayHintsConfig { surprise_inference_hints: true, ..DISABLED_CONFIG },
+            InlayHintsConfig { adjustment_hints: true, ..DISABLED_CONFIG },
+            InlayHintsConfig { question_mark_conversion_hints: true, ..DISABLED_CONFIG },
+            InlayHintsConfig { index_output_hints: true, ..DISABLED_CONFIG },
+            InlayHintsConfig { operator_hints: true, ..DISABLED_CONFIG },
+            InlayHintsConfig { deref_hints: true, ..DISABLED_CONFIG },
+            InlayHintsConfig { arg_list_type_hints: true, ..DISABLED_CONFIG },
+            InlayHintsConfig { literal_type_hints: true, ..DISABLED_CONFIG },
+            InlayHintsConfig { inferred_constructor_type_hints: true, ..DISABLED_CONFIG },
+            InlayHintsConfig { arg_order_mismatch_hints: true, ..DISABLED_CONFIG },
+            InlayHintsConfig { closure_capture_hints: true, ..DISABLED_CONFIG },
+        ] {
+            assert!(config.any_kind_enabled(), "{:?}", config);
+        }
+    }
+
+    #[test]
+    fn hint_padding_follows_kind() {
+        let (analysis, file_id) = fixture::file(
+            r#"
+struct A(B);
+impl A { fn into_b(self) -> B { self.0 } }
+struct B;
+impl B { fn into_a(self) -> A { A(self) } }
+fn make(count: i32, flag: bool) -> A { A(B) }
+fn main() {
+    let a = make(1, true)
+        .into_b()
+        .into_a();
+}
+"#,
+        );
+        let config = InlayHintsConfig {
+            type_hints: true,
+            parameter_hints: true,
+            chaining_hints: true,
+            ..DISABLED_CONFIG
+        };
+        let inlay_hints = analysis.inlay_hints(file_id, &config).unwrap();
+        let actual = inlay_hints
+            .iter()
+            .map(|it| (it.kind, it.label.to_string(), it.pad_left, it.pad_right))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            actual,
+            vec![
+                (InlayKind::TypeHint, "A".to_string(), true, false),
+                (InlayKind::ChainingHint, "A".to_string(), true, false),
+                (InlayKind::ChainingHint, "B".to_string(), true, false),
+                (InlayKind::ParameterHint, "count".to_string(), false, true),
+                (InlayKind::ParameterHint, "flag".to_string(), false, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn no_hints_inside_macro_rules_definitions() {
         check_with_config(
             InlayHintsConfig {
                 type_hints: true,
-                parameter_hints: false,
-                chaining_hints: false,
-                max_length: None,
+                parameter_hints: true,
+                chaining_hints: true,
+                ..DISABLED_CONFIG
+            },
+            r#"
+macro_rules! bind {
+    ($e:expr) => {
+        let bound = $e;
+        let other = foo(bound);
+    };
+}
+fn foo(amount: i32) -> i32 { amount }
+fn main() {
+    let after = 1;
+      //^^^^^ i32
+    foo(after);
+      //^^^^^ amount
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn hint_function_filter() {
+        let fixture = r#"
+fn foo(a: i32, b: i32) -> i32 { a + b }
+fn review_me() {
+    let a = 1;
+      //^ i32
+    foo(a, a);
+         //^ b
+}
+fn other() {
+    let y = foo(3, 4);
+}
+const C: i32 = foo(5, 6);
+"#;
+        for filter in &["review", "review_*", "*_me", "r*w*e"] {
+            check_with_config(
+                InlayHintsConfig {
+                    hint_function_filter: Some(filter.to_string()),
+                    ..InlayHintsConfig::default()
+                },
+                fixture,
+            );
+        }
+        check_with_config(
+            InlayHintsConfig {
+                hint_function_filter: Some("review_*x".to_string()),
+                ..InlayHintsConfig::default()
             },
+            &fixture.replace("      //^ i32\n", "").replace("         //^ b\n", ""),
+        );
+    }
+
+    #[test]
+    fn param_hints_for_partially_typed_calls() {
+        check_with_config(
+            InlayHintsConfig { parameter_hints: true, ..DISABLED_CONFIG },
+            r#"
+fn foo(first: i32, second: i32, third: i32) -> i32 { first }
+fn main() {
+    foo(1, );
+      //^ first
+    foo(1,      2, );
+      //^ first ^ second
+    foo(1, , 3);
+      //^ first
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn raw_identifier_param_hints() {
+        check_with_config(
+            InlayHintsConfig { parameter_hints: true, ..DISABLED_CONFIG },
+            r#"
+fn check(r#type: u32, r#match: bool) {}
+fn r#match(r#match: u32) {}
+fn main() {
+    let r#type = 1;
+    let _type = 2;
+    check(r#type, true);
+                //^^^^ match
+    check(_type,  false);
+                //^^^^^ match
+    check(2,       true);
+        //^ type   ^^^^ match
+    r#match(3);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn type_hints_only() {
+        check_with_config(
+            InlayHintsConfig { type_hints: true, ..DISABLED_CONFIG },
             r#"
 fn foo(a: i32, b: i32) -> i32 { a + b }
 fn main() {
@@ -491,6 +382,51 @@ fn main() {
       //^^^^ || -> Test<i32>
 }"#,
         );
+        check_with_config(
+            InlayHintsConfig {
+                type_hints: true,
+                closure_style: ClosureStyle::ImplFn,
+                ..DISABLED_CONFIG
+            },
+            r#"
+struct Test<K, T = u8> { k: K, t: T }
+
+fn main() {
+    let zz = Test { t: 23u8, k: 33 };
+      //^^ Test<i32>
+    let test = || zz;
+      //^^^^ impl FnOnce() -> Test<i32>
+}"#,
+        );
+    }
+
+    #[test]
+    fn default_generic_types_displayed_on_request() {
+        let fixture = r#"
+struct Test<K, T = u8> { k: K, t: T }
+
+fn main() {
+    let zz = Test { t: 23u8, k: 33 };
+      //^^ Test<i32, u8>
+    let zz_ref = &zz;
+      //^^^^^^ &Test<i32, u8>
+    let zz_slice = [zz];
+      //^^^^^^^^ [Test<i32, u8>; _]
+    let test = || zz_ref;
+      //^^^^ || -> &Test<i32, u8>
+}"#;
+        check_with_config(
+            InlayHintsConfig {
+                type_hints: true,
+                show_default_generic_args: true,
+                ..DISABLED_CONFIG
+            },
+            fixture,
+        );
+        check_with_config(
+            InlayHintsConfig { type_hints: true, ..DISABLED_CONFIG },
+            &fixture.replace("Test<i32, u8>", "Test<i32>"),
+        );
     }
 
     #[test]
@@ -552,54 +488,249 @@ fn main() {
       //^^^^^^^^^ || -> i32
 }"#,
         );
-    }
-
-    #[test]
-    fn if_expr() {
-        check(
+        check_with_config(
+            InlayHintsConfig {
+                type_hints: true,
+                closure_style: ClosureStyle::ImplFn,
+                ..DISABLED_CONFIG
+            },
             r#"
-enum Option<T> { None, Some(T) }
-use Option::*;
+#[lang = "fn_once"]
+trait FnOnce<Args> { type Output; }
+#[lang = "fn_mut"]
+trait FnMut<Args>: FnOnce<Args> {}
+#[lang = "fn"]
+trait Fn<Args>: FnMut<Args> {}
 
-struct Test { a: Option<u32>, b: u8 }
+fn main() {
+    let multiply =
+      //^^^^^^^^ impl Fn(i32, i32) -> i32
+      | a,     b| a * b
+      //^ i32  ^ i32
+    ;
+
+    let _: i32 = multiply(1, 2);
+    let multiply_ref = &multiply;
+      //^^^^^^^^^^^^ &impl Fn(i32, i32) -> i32
 
+    let noop = || ();
+      //^^^^ impl Fn()
+}"#,
+        );
+        check_with_config(
+            InlayHintsConfig {
+                type_hints: true,
+                closure_style: ClosureStyle::ClosureWithId,
+                ..DISABLED_CONFIG
+            },
+            r#"
 fn main() {
-    let test = Some(Test { a: Some(3), b: 1 });
-      //^^^^ Option<Test>
-    if let None = &test {};
-    if let test = &test {};
-         //^^^^ &Option<Test>
-    if let Some(test) = &test {};
-              //^^^^ &Test
-    if let Some(Test { a,             b }) = &test {};
-                     //^ &Option<u32> ^ &u8
-    if let Some(Test { a: x,             b: y }) = &test {};
-                        //^ &Option<u32>    ^ &u8
-    if let Some(Test { a: Some(x),  b: y }) = &test {};
-                             //^ &u32  ^ &u8
-    if let Some(Test { a: None,  b: y }) = &test {};
-                                  //^ &u8
-    if let Some(Test { b: y, .. }) = &test {};
-                        //^ &u8
-    if test == None {}
+    let return_42 = || 42;
+      //^^^^^^^^^ {closure#1}
+}"#,
+        );
+        check_with_config(
+            InlayHintsConfig {
+                type_hints: true,
+                closure_style: ClosureStyle::Hide,
+                ..DISABLED_CONFIG
+            },
+            r#"
+fn main() {
+    let return_42 = || 42;
+    let return_42_ref = &return_42;
+      //^^^^^^^^^^^^^ &…
 }"#,
         );
     }
 
     #[test]
-    fn while_expr() {
-        check(
+    fn nested_closure_parameters() {
+        check_with_config(
+            InlayHintsConfig { type_hints: true, ..DISABLED_CONFIG },
             r#"
-enum Option<T> { None, Some(T) }
-use Option::*;
+#[lang = "fn_once"]
+trait FnOnce<Args> {
+    type Output;
+}
 
-struct Test { a: Option<u32>, b: u8 }
+struct Outer;
+struct Inner;
+
+fn outer<F: FnOnce(Outer) -> R, R>(f: F) -> R { f(Outer) }
+fn inner<F: FnOnce(&Outer) -> Inner>(outer: &Outer, f: F) -> Inner { f(outer) }
 
 fn main() {
-    let test = Some(Test { a: Some(3), b: 1 });
-      //^^^^ Option<Test>
-    while let Some(Test { a: Some(x),  b: y }) = &test {};
-                                //^ &u32  ^ &u8
+    let result = outer(|a| inner(&a, |b| Inner));
+      //^^^^^^ Inner    ^ Outer       ^ &Outer
+}"#,
+        );
+    }
+
+    #[test]
+    fn map_entry_or_insert_with_binding() {
+        check_with_config(
+            InlayHintsConfig { type_hints: true, ..DISABLED_CONFIG },
+            r#"
+#[lang = "fn_once"]
+trait FnOnce<Args> {
+    type Output;
+}
+
+struct Vec<T>(T);
+impl<T> Vec<T> {
+    fn new() -> Self { loop {} }
+}
+
+struct HashMap<K, V>(K, V);
+impl<K, V> HashMap<K, V> {
+    fn new() -> Self { loop {} }
+    fn entry(&mut self, key: K) -> Entry<'_, K, V> { loop {} }
+}
+
+struct Entry<'a, K, V>(&'a mut HashMap<K, V>);
+impl<'a, K, V> Entry<'a, K, V> {
+    fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V { loop {} }
+}
+
+fn main() {
+    let mut map: HashMap<u32, Vec<u8>> = HashMap::new();
+    let v = map.entry(1).or_insert_with(Vec::new);
+      //^ &mut Vec<u8>
+    let w = map.entry(2).or_insert_with(|| Vec::new());
+      //^ &mut Vec<u8>
+}"#,
+        );
+    }
+
+    #[test]
+    fn control_flow_try_unwraps_to_continue_type() {
+        check(
+            r#"
+use core::ops::ControlFlow;
+
+fn step(n: u32) -> ControlFlow<&'static str, u32> { ControlFlow::Continue(n) }
+
+fn run() -> ControlFlow<&'static str, u32> {
+    let next = step(1)?;
+      //^^^^ u32
+    let flow = step(next);
+      //^^^^ ControlFlow<&str, u32>
+    flow
+}"#,
+        );
+    }
+
+    #[test]
+    fn boxed_dyn_fn_closure_parameters() {
+        check_with_config(
+            InlayHintsConfig { type_hints: true, ..DISABLED_CONFIG },
+            r#"
+#[lang = "sized"]
+trait Sized {}
+#[lang = "fn_once"]
+trait FnOnce<Args> { type Output; }
+#[lang = "fn_mut"]
+trait FnMut<Args>: FnOnce<Args> {}
+#[lang = "fn"]
+trait Fn<Args>: FnMut<Args> {}
+
+#[lang = "owned_box"]
+struct Box<T: ?Sized>(*mut T);
+impl<T> Box<T> {
+    fn new(t: T) -> Self { loop {} }
+}
+#[lang = "unsize"]
+trait Unsize<T: ?Sized> {}
+#[lang = "coerce_unsized"]
+trait CoerceUnsized<T> {}
+impl<T: ?Sized + Unsize<U>, U: ?Sized> CoerceUnsized<Box<U>> for Box<T> {}
+
+struct Vec<T> {}
+impl<T> Vec<T> {
+    fn new() -> Self { Vec {} }
+    fn push(&mut self, t: T) {}
+}
+
+fn main() {
+    let mut handlers: Vec<Box<dyn Fn(i32)>> = Vec::new();
+    handlers.push(Box::new(|code| {}));
+                          //^^^^ i32
+}"#,
+        );
+    }
+
+    #[test]
+    fn surprise_inference_hints() {
+        check_with_config(
+            InlayHintsConfig { surprise_inference_hints: true, ..DISABLED_CONFIG },
+            r#"
+fn takes_usize(n: usize) {}
+fn takes_f32(x: f32) {}
+
+fn main() {
+    let len = 3;
+      //^^^ usize, not i32
+    takes_usize(len);
+    let ratio = -(0.5);
+      //^^^^^ f32, not f64
+    takes_f32(ratio);
+
+    let count = 3;
+    let _: i32 = count;
+    let scale = 2.0;
+    let suffixed = 3u8;
+    let annotated: usize = 3;
+}"#,
+        );
+    }
+
+    #[test]
+    fn if_expr() {
+        check(
+            r#"
+enum Option<T> { None, Some(T) }
+use Option::*;
+
+struct Test { a: Option<u32>, b: u8 }
+
+fn main() {
+    let test = Some(Test { a: Some(3), b: 1 });
+      //^^^^ Option<Test>
+    if let None = &test {};
+    if let test = &test {};
+         //^^^^ &Option<Test>
+    if let Some(test) = &test {};
+              //^^^^ &Test
+    if let Some(Test { a,             b }) = &test {};
+                     //^ &Option<u32> ^ &u8
+    if let Some(Test { a: x,             b: y }) = &test {};
+                        //^ &Option<u32>    ^ &u8
+    if let Some(Test { a: Some(x),  b: y }) = &test {};
+                             //^ &u32  ^ &u8
+    if let Some(Test { a: None,  b: y }) = &test {};
+                                  //^ &u8
+    if let Some(Test { b: y, .. }) = &test {};
+                        //^ &u8
+    if test == None {}
+}"#,
+        );
+    }
+
+    #[test]
+    fn while_expr() {
+        check(
+            r#"
+enum Option<T> { None, Some(T) }
+use Option::*;
+
+struct Test { a: Option<u32>, b: u8 }
+
+fn main() {
+    let test = Some(Test { a: Some(3), b: 1 });
+      //^^^^ Option<Test>
+    while let Some(Test { a: Some(x),  b: y }) = &test {};
+                                //^ &u32  ^ &u8
 }"#,
         );
     }
@@ -646,6 +777,20 @@ fn main() {
         );
     }
 
+    #[test]
+    fn hint_truncation_counts_chars() {
+        check_with_config(
+            InlayHintsConfig { max_length: Some(5), ..Default::default() },
+            r#"
+struct Äöü<T>(T);
+
+fn main() {
+    let a = Äöü(Äöü(0u32));
+      //^ Äöü<Äöü<…>>
+}"#,
+        );
+    }
+
     #[test]
     fn function_call_parameter_hint() {
         check(
@@ -709,6 +854,27 @@ fn main() {
         );
     }
 
+    #[test]
+    fn self_param_hints_disabled() {
+        check_with_config(
+            InlayHintsConfig { parameter_hints: true, ..DISABLED_CONFIG },
+            r#"
+struct Test {}
+
+impl Test {
+    fn method(&self, mut param: i32, other: i32) -> i32 { param * other }
+}
+
+fn main() {
+    let t: Test = Test {};
+    Test::method(&t,      3456,       7);
+                        //^^^^ param  ^ other
+    t.method(123,       9);
+           //^^^ param  ^ other
+}"#,
+        );
+    }
+
     #[test]
     fn omitted_parameters_hints_heuristics() {
         check_with_config(
@@ -824,12 +990,7 @@ fn main() {
     #[test]
     fn chaining_hints_ignore_comments() {
         check_expect(
-            InlayHintsConfig {
-                parameter_hints: false,
-                type_hints: false,
-                chaining_hints: true,
-                max_length: None,
-            },
+            InlayHintsConfig { chaining_hints: true, ..DISABLED_CONFIG },
             r#"
 struct A(B);
 impl A { fn into_b(self) -> B { self.0 } }
@@ -846,14 +1007,44 @@ fn main() {
             expect![[r#"
                 [
                     InlayHint {
-                        range: 148..173,
+                        range: 148..155,
                         kind: ChainingHint,
-                        label: "B",
+                        label: [
+                            InlayHintLabelPart {
+                                text: "A",
+                                goto: FileRange {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    range: 8..9,
+                                },
+                            },
+                        ],
+                        tooltip: None,
+                        id: None,
+                        pad_left: true,
+                        pad_right: false,
+                        text_edit: None,
                     },
                     InlayHint {
-                        range: 148..155,
+                        range: 148..173,
                         kind: ChainingHint,
-                        label: "A",
+                        label: [
+                            InlayHintLabelPart {
+                                text: "B",
+                                goto: FileRange {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    range: 64..65,
+                                },
+                            },
+                        ],
+                        tooltip: None,
+                        id: None,
+                        pad_left: true,
+                        pad_right: false,
+                        text_edit: None,
                     },
                 ]
             "#]],
@@ -863,12 +1054,7 @@ fn main() {
     #[test]
     fn chaining_hints_without_newlines() {
         check_with_config(
-            InlayHintsConfig {
-                parameter_hints: false,
-                type_hints: false,
-                chaining_hints: true,
-                max_length: None,
-            },
+            InlayHintsConfig { chaining_hints: true, ..DISABLED_CONFIG },
             r#"
 struct A(B);
 impl A { fn into_b(self) -> B { self.0 } }
@@ -885,12 +1071,7 @@ fn main() {
     #[test]
     fn struct_access_chaining_hints() {
         check_expect(
-            InlayHintsConfig {
-                parameter_hints: false,
-                type_hints: false,
-                chaining_hints: true,
-                max_length: None,
-            },
+            InlayHintsConfig { chaining_hints: true, ..DISABLED_CONFIG },
             r#"
 struct A { pub b: B }
 struct B { pub c: C }
@@ -912,14 +1093,44 @@ fn main() {
             expect![[r#"
                 [
                     InlayHint {
-                        range: 144..191,
+                        range: 144..180,
                         kind: ChainingHint,
-                        label: "C",
+                        label: [
+                            InlayHintLabelPart {
+                                text: "B",
+                                goto: FileRange {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    range: 30..31,
+                                },
+                            },
+                        ],
+                        tooltip: None,
+                        id: None,
+                        pad_left: true,
+                        pad_right: false,
+                        text_edit: None,
                     },
                     InlayHint {
-                        range: 144..180,
+                        range: 144..191,
                         kind: ChainingHint,
-                        label: "B",
+                        label: [
+                            InlayHintLabelPart {
+                                text: "C",
+                                goto: FileRange {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    range: 52..53,
+                                },
+                            },
+                        ],
+                        tooltip: None,
+                        id: None,
+                        pad_left: true,
+                        pad_right: false,
+                        text_edit: None,
                     },
                 ]
             "#]],
@@ -927,260 +1138,3465 @@ fn main() {
     }
 
     #[test]
-    fn generic_chaining_hints() {
-        check_expect(
-            InlayHintsConfig {
-                parameter_hints: false,
-                type_hints: false,
-                chaining_hints: true,
-                max_length: None,
-            },
+    fn range_limited_hints() {
+        check_range(
+            InlayHintsConfig { type_hints: true, chaining_hints: true, ..DISABLED_CONFIG },
             r#"
-struct A<T>(T);
-struct B<T>(T);
-struct C<T>(T);
-struct X<T,R>(T, R);
+struct A(B);
+impl A { fn into_b(self) -> B { self.0 } }
+struct B(C);
+impl B { fn into_c(self) -> C { self.0 } }
+struct C;
 
-impl<T> A<T> {
-    fn new(t: T) -> Self { A(t) }
-    fn into_b(self) -> B<T> { B(self.0) }
-}
-impl<T> B<T> {
-    fn into_c(self) -> C<T> { C(self.0) }
-}
 fn main() {
-    let c = A::new(X(42, true))
+    let above = 92;
+    let c = A(B(C))
         .into_b()
-        .into_c();
+<|>        .into_c();
+    let inside = 42;
+<|>    let below = 1;
 }
 "#,
             expect![[r#"
                 [
                     InlayHint {
-                        range: 247..284,
+                        range: 168..175,
                         kind: ChainingHint,
-                        label: "B<X<i32, bool>>",
+                        label: [
+                            InlayHintLabelPart {
+                                text: "A",
+                                goto: FileRange {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    range: 8..9,
+                                },
+                            },
+                        ],
+                        tooltip: None,
+                        id: None,
+                        pad_left: true,
+                        pad_right: false,
+                        text_edit: None,
                     },
                     InlayHint {
-                        range: 247..266,
+                        range: 168..193,
                         kind: ChainingHint,
-                        label: "A<X<i32, bool>>",
+                        label: [
+                            InlayHintLabelPart {
+                                text: "B",
+                                goto: FileRange {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    range: 64..65,
+                                },
+                            },
+                        ],
+                        tooltip: None,
+                        id: None,
+                        pad_left: true,
+                        pad_right: false,
+                        text_edit: None,
+                    },
+                    InlayHint {
+                        range: 221..227,
+                        kind: TypeHint,
+                        label: "i32",
+                        tooltip: None,
+                        id: None,
+                        pad_left: true,
+                        pad_right: false,
+                        text_edit: Some(
+                            TextEdit {
+                                indels: [
+                                    Indel {
+                                        insert: ": i32",
+                                        delete: 227..227,
+                                    },
+                                ],
+                            },
+                        ),
                     },
                 ]
             "#]],
         );
     }
 
+    /// Checks that the hints computed with `lazy_labels` are the eager ones,
+    /// and that those left with an empty label, of which there must be some,
+    /// resolve to the eager label.
+    fn check_lazy_labels(config: InlayHintsConfig, ra_fixture: &str) {
+        let ra_fixture =
+            format!("//- /main.rs crate:main deps:core\n{}\n{}", ra_fixture, FamousDefs::FIXTURE);
+        let (analysis, file_id) = fixture::file(&ra_fixture);
+        let eager = analysis.inlay_hints(file_id, &config).unwrap();
+        let lazy = analysis
+            .inlay_hints(file_id, &InlayHintsConfig { lazy_labels: true, ..config.clone() })
+            .unwrap();
+        assert_eq!(eager.len(), lazy.len());
+        assert!(lazy.iter().any(|hint| hint.label.is_empty()));
+
+        for (eager, lazy) in eager.iter().zip(lazy.iter()) {
+            assert_eq!((eager.range, &eager.kind), (lazy.range, &lazy.kind));
+            let label = if lazy.label.is_empty() {
+                analysis
+                    .resolve_inlay_hint(file_id, lazy.range, lazy.kind, &config)
+                    .unwrap()
+                    .unwrap()
+                    .label
+            } else {
+                lazy.label.clone()
+            };
+            assert_eq!(eager.label, label);
+        }
+    }
+
     #[test]
-    fn incomplete_for_no_hint() {
-        check(
+    fn lazy_labels_resolve_to_eager_labels() {
+        check_lazy_labels(
+            InlayHintsConfig {
+                surprise_inference_hints: true,
+                arg_list_type_hints: true,
+                max_length: Some(12),
+                ..InlayHintsConfig::default()
+            },
             r#"
+use core::iter;
+
+struct VeryLongOuterName<T>(T);
+struct A(B);
+impl A { fn into_b(self) -> B { self.0 } }
+struct B;
+
+fn takes_u8(n: u8) {}
+
 fn main() {
-    let data = &[1i32, 2, 3];
-      //^^^^ &[i32; _]
-    for i
-}"#,
+    let long = VeryLongOuterName(VeryLongOuterName(0u32));
+    let repeated = iter::repeat(0).take(5);
+    let small = 1;
+    takes_u8(small);
+    let whole @ A(_) = A(B);
+    let b = A(B)
+        .into_b();
+}
+"#,
         );
-        check(
+    }
+
+    #[test]
+    fn hint_tooltips() {
+        check_expect(
+            InlayHintsConfig {
+                type_hints: true,
+                parameter_hints: true,
+                max_length: Some(8),
+                hint_tooltips: true,
+                ..DISABLED_CONFIG
+            },
             r#"
-pub struct Vec<T> {}
+struct VeryLongOuterName<T>(T);
 
-impl<T> Vec<T> {
-    pub fn new() -> Self { Vec {} }
-    pub fn push(&mut self, t: T) {}
-}
+fn consume(value_to_consume: VeryLongOuterName<u32>) {}
 
-impl<T> IntoIterator for Vec<T> {
-    type Item=T;
+fn main() {
+    let long = VeryLongOuterName(0u32);
+    consume(long);
 }
+"#,
+            expect![[r#"
+                [
+                    InlayHint {
+                        range: 111..115,
+                        kind: TypeHint,
+                        label: [
+                            InlayHintLabelPart {
+                                text: "VeryLongOuterName",
+                                goto: FileRange {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    range: 8..25,
+                                },
+                            },
+                            "<…>",
+                        ],
+                        tooltip: Some(
+                            Markup {
+                                text: "```rust\nVeryLongOuterName<u32>\n```",
+                            },
+                        ),
+                        id: None,
+                        pad_left: true,
+                        pad_right: false,
+                        text_edit: Some(
+                            TextEdit {
+                                indels: [
+                                    Indel {
+                                        insert: ": VeryLongOuterName<u32>",
+                                        delete: 115..115,
+                                    },
+                                ],
+                            },
+                        ),
+                    },
+                    InlayHint {
+                        range: 155..159,
+                        kind: ParameterHint,
+                        label: "value_to_consume",
+                        tooltip: Some(
+                            Markup {
+                                text: "```rust\nvalue_to_consume: VeryLongOuterName<u32>\n```",
+                            },
+                        ),
+                        id: None,
+                        pad_left: false,
+                        pad_right: true,
+                        text_edit: None,
+                    },
+                ]
+            "#]],
+        );
+    }
 
-fn main() {
-    let mut data = Vec::new();
-      //^^^^^^^^ Vec<&str>
-    data.push("foo");
-    for i in
+    #[test]
+    fn ufcs_receiver_adjustment_hints() {
+        check_with_config(
+            InlayHintsConfig { adjustment_hints: true, ..DISABLED_CONFIG },
+            r#"
+struct Counter;
 
-    println!("Unit expr");
+trait Count {
+    fn count(&self) -> usize;
+    fn reset(&mut self);
 }
 
-//- /alloc.rs crate:alloc deps:core
-mod collections {
-    struct Vec<T> {}
-    impl<T> Vec<T> {
-        fn new() -> Self { Vec {} }
-        fn push(&mut self, t: T) { }
-    }
-    impl<T> IntoIterator for Vec<T> {
-        type Item=T;
-    }
+impl Count for Counter {
+    fn count(&self) -> usize { 0 }
+    fn reset(&mut self) {}
+}
+
+fn main() {
+    let mut counter = Counter;
+    Count::count(counter);
+               //^^^^^^^ &
+    Count::reset(counter);
+               //^^^^^^^ &mut
+    Count::count(&counter);
+    counter.count();
 }
 "#,
         );
     }
 
     #[test]
-    fn complete_for_hint() {
-        check(
+    fn no_borrow_adjustment_hints_for_function_arguments() {
+        check_with_config(
+            InlayHintsConfig { adjustment_hints: true, ..DISABLED_CONFIG },
             r#"
-pub struct Vec<T> {}
+struct Counter;
 
-impl<T> Vec<T> {
-    pub fn new() -> Self { Vec {} }
-    pub fn push(&mut self, t: T) {}
-}
+fn by_ref(counter: &Counter) {}
+fn by_mut(counter: &mut Counter) {}
 
-impl<T> IntoIterator for Vec<T> {
-    type Item=T;
+fn main() {
+    let mut counter = Counter;
+    by_ref(counter);
+    by_mut(counter);
 }
+"#,
+        );
+    }
 
-fn main() {
-    let mut data = Vec::new();
-      //^^^^^^^^ Vec<&str>
-    data.push("foo");
-    for i in data {
-      //^ &str
-      let z = i;
-        //^ &str
-    }
+    #[test]
+    fn combined_colocated_hints() {
+        check_with_kinds(
+            InlayHintsConfig {
+                parameter_hints: true,
+                self_param_hints: true,
+                adjustment_hints: true,
+                combine_colocated_hints: true,
+                ..DISABLED_CONFIG
+            },
+            r#"
+struct Counter;
+
+trait Count {
+    fn count(&self) -> usize;
+    fn reset(&mut self);
 }
-//- /alloc.rs crate:alloc deps:core
-mod collections {
-    struct Vec<T> {}
-    impl<T> Vec<T> {
-        fn new() -> Self { Vec {} }
-        fn push(&mut self, t: T) { }
-    }
-    impl<T> IntoIterator for Vec<T> {
-        type Item=T;
-    }
+
+impl Count for Counter {
+    fn count(&self) -> usize { 0 }
+    fn reset(&mut self) {}
+}
+
+fn main() {
+    let mut counter = Counter;
+    Count::count(counter);
+               //^^^^^^^ param: (&) &self
+    Count::reset(counter);
+               //^^^^^^^ param: (&mut) &mut self
+    Count::count(&counter);
+               //^^^^^^^^ param: &self
 }
 "#,
         );
     }
 
     #[test]
-    fn multi_dyn_trait_bounds() {
+    fn tuple_struct_field_doc_hints() {
         check_with_config(
             InlayHintsConfig {
                 type_hints: true,
-                parameter_hints: false,
-                chaining_hints: false,
-                max_length: None,
+                tuple_struct_field_doc_hints: true,
+                ..DISABLED_CONFIG
             },
             r#"
-pub struct Vec<T> {}
+struct Size(
+    /// Width in pixels.
+    u32,
+    /// Height in pixels.
+    u32,
+);
 
-impl<T> Vec<T> {
-    pub fn new() -> Self { Vec {} }
-}
+struct Tagged(
+    u8,
+    /// `Label` of the value.
+    &'static str,
+);
 
-pub struct Box<T> {}
+struct Plain(u32, u32);
 
-trait Display {}
-trait Sync {}
+fn size() -> Size { Size(1, 2) }
+fn tagged() -> Tagged { Tagged(1, "") }
+fn plain() -> Plain { Plain(1, 2) }
 
 fn main() {
-    let _v = Vec::<Box<&(dyn Display + Sync)>>::new();
-      //^^ Vec<Box<&(dyn Display + Sync)>>
-    let _v = Vec::<Box<*const (dyn Display + Sync)>>::new();
-      //^^ Vec<Box<*const (dyn Display + Sync)>>
-    let _v = Vec::<Box<dyn Display + Sync>>::new();
-      //^^ Vec<Box<dyn Display + Sync>>
+    let s = size();
+      //^ Size(width: u32, height: u32)
+    let t = tagged();
+      //^ Tagged(u8, label: &str)
+    let p = plain();
+      //^ Plain
 }
 "#,
         );
     }
 
     #[test]
-    fn shorten_iterator_hints() {
+    fn type_hint_label_parts() {
+        let (analysis, file_id) = fixture::file(
+            r#"
+//- /main.rs crate:main deps:dep
+struct Local<T>(T);
+struct Pair<A, B>(A, B);
+
+fn main() {
+    let local = Local(dep::Remote);
+    let remote = &dep::Remote;
+    let builtin = 92;
+    let pair = Pair(Local(0), Pair(dep::Remote, Local(0)));
+}
+//- /dep.rs crate:dep
+pub struct Remote;
+"#,
+        );
+        let render = |config: &InlayHintsConfig| {
+            let hints = analysis.inlay_hints(file_id, config).unwrap();
+            hints
+                .iter()
+                .map(|hint| {
+                    hint.label
+                        .parts
+                        .iter()
+                        .map(|part| match part.goto {
+                            Some(goto) => {
+                                let text = analysis.file_text(goto.file_id).unwrap();
+                                assert_eq!(&text[goto.range], part.text);
+                                let krate = if goto.file_id == file_id { "main" } else { "dep" };
+                                format!("[{}::{}]", krate, part.text)
+                            }
+                            None => part.text.clone(),
+                        })
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let config = InlayHintsConfig { type_hints: true, ..DISABLED_CONFIG };
+        assert_eq!(
+            render(&config),
+            vec![
+                "[main::Local]<[dep::Remote]>",
+                "&[dep::Remote]",
+                "i32",
+                "[main::Pair]<[main::Local]<i32>, [main::Pair]<[dep::Remote], [main::Local]<i32>>>",
+            ]
+        );
+
+        let config = InlayHintsConfig { max_length: Some(20), ..config };
+        assert_eq!(
+            render(&config),
+            vec![
+                "[main::Local]<[dep::Remote]>",
+                "&[dep::Remote]",
+                "i32",
+                "[main::Pair]<[main::Local]<i32>, [main::Pair]<…, …>>",
+            ]
+        );
+    }
+
+    #[test]
+    fn match_result_coerced_to_common_type() {
         check_with_config(
-            InlayHintsConfig {
-                parameter_hints: false,
-                type_hints: true,
-                chaining_hints: false,
-                max_length: None,
-            },
+            InlayHintsConfig { type_hints: true, ..DISABLED_CONFIG },
             r#"
-use core::iter;
+#[lang = "sized"]
+trait Sized {}
+#[lang = "unsize"]
+trait Unsize<T: ?Sized> {}
+#[lang = "coerce_unsized"]
+trait CoerceUnsized<T> {}
+impl<'a, T: ?Sized + Unsize<U>, U: ?Sized> CoerceUnsized<&'a U> for &'a T {}
+
+trait Shape {}
+struct Circle;
+struct Square;
+impl Shape for Circle {}
+impl Shape for Square {}
+
+fn one() -> u8 { 1 }
+fn two() -> u8 { 2 }
+
+fn main(flag: bool) {
+    let shape = match flag {
+      //^^^^^ &dyn Shape
+        true => &Circle as &dyn Shape,
+        false => &Square,
+    };
+    let pick = match flag {
+      //^^^^ fn() -> u8
+        true => one,
+        false => two,
+    };
+}
+"#,
+        );
+    }
 
-struct MyIter;
+    #[test]
+    fn hint_ids_survive_unrelated_edits() {
+        let ids = |text: &str| {
+            let (analysis, file_id) = fixture::file(text);
+            let config = InlayHintsConfig { hint_ids: true, ..InlayHintsConfig::default() };
+            let hints = analysis.inlay_hints(file_id, &config).unwrap();
+            hints
+                .into_iter()
+                .map(|hint| (hint.label.to_string(), hint.id.unwrap()))
+                .collect::<Vec<_>>()
+        };
+
+        let before = ids(r#"
+fn helper() -> u32 { 1 }
+fn add(left: u32, right: u32) -> u32 { left + right }
 
-impl Iterator for MyIter {
-    type Item = ();
-    fn next(&mut self) -> Option<Self::Item> {
-        None
+fn main() {
+    let x = helper();
+    let y = add(x, 2);
+}
+"#);
+        let after = ids(r#"
+fn helper() -> u32 { 1 + 100 * 1000 }
+fn add(left: u32, right: u32) -> u32 { left + right }
+
+fn main() {
+    let x = helper();
+    let y = add(x, 2);
+}
+"#);
+        assert_eq!(before.len(), 4);
+        assert_eq!(before, after);
+
+        let unique = before.iter().map(|(_, id)| *id).collect::<FxHashSet<_>>();
+        assert_eq!(unique.len(), before.len());
+
+        // Edits in the same function shift the hints after them, but don't
+        // change their ids unless they add or remove siblings on the path.
+        let edited = ids(r#"
+fn helper() -> u32 { 1 }
+fn add(left: u32, right: u32) -> u32 { left + right }
+
+fn main() {
+    // Computes the sum.
+    let x = helper() + helper() * 2;
+    let y = add(x, 2);
+}
+"#);
+        assert_eq!(before, edited);
+
+        let moved = ids(r#"
+fn helper() -> u32 { 1 }
+fn add(left: u32, right: u32) -> u32 { left + right }
+
+fn main() {
+    let w = helper();
+    let x = helper();
+    let y = add(x, 2);
+}
+"#);
+        assert_ne!(moved[1], before[0]);
     }
+
+    #[test]
+    fn inlay_hint_at_matches_full_traversal() {
+        let (analysis, file_id) = fixture::file(
+            r#"
+struct Wrapper(u32);
+impl Wrapper {
+    fn get(&self) -> u32 { self.0 }
+    fn with(self, value: u32) -> Wrapper { Wrapper(value) }
 }
 
+fn add(left: u32, right: u32) -> u32 { left + right }
+
 fn main() {
-    let _x = MyIter;
-      //^^ MyIter
-    let _x = iter::repeat(0);
-      //^^ impl Iterator<Item = i32>
-    fn generic<T: Clone>(t: T) {
-        let _x = iter::repeat(t);
-          //^^ impl Iterator<Item = T>
-        let _chained = iter::repeat(t).take(10);
-          //^^^^^^^^ impl Iterator<Item = T>
+    let wrapper = Wrapper(1);
+    let sum = add(wrapper.get(), add(2, 3));
+    let chained = Wrapper(4)
+        .with(5)
+        .with(6)
+        .get();
+}
+"#,
+        );
+        let config = InlayHintsConfig::default();
+        let all = analysis.inlay_hints(file_id, &config).unwrap();
+        let render = |hint: &InlayHint| (hint.range, hint.kind, hint.label.to_string());
+
+        let text = analysis.file_text(file_id).unwrap();
+        let mut found = 0;
+        for offset in 0..text.len() {
+            let offset = TextSize::from(offset as u32);
+            let actual = analysis
+                .inlay_hint_at(FilePosition { file_id, offset }, &config)
+                .unwrap()
+                .map(|hint| render(&hint));
+            let covering = || all.iter().filter(|hint| hint.range.contains(offset));
+            let smallest = covering().map(|hint| hint.range.len()).min();
+            let expected = covering().find(|hint| Some(hint.range.len()) == smallest).map(&render);
+            assert_eq!(actual, expected, "at offset {:?}", offset);
+            found += actual.is_some() as usize;
+        }
+        assert!(found > 0);
     }
+
+    #[test]
+    fn arbitrary_self_type_param_hints() {
+        check_with_config(
+            InlayHintsConfig { parameter_hints: true, self_param_hints: true, ..DISABLED_CONFIG },
+            r#"
+struct Pin<P> { pointer: P }
+struct Context;
+enum Poll { Ready, Pending }
+
+trait Future {
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll;
+    fn consume(mut   self: Box<Self>);
+}
+
+struct Ready;
+impl Future for Ready {
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll { Poll::Ready }
+    fn consume(mut   self: Box<Self>) {}
+}
+
+fn main() {
+    let mut ready = Ready;
+    let mut context = Context;
+    Future::poll(
+        Pin { pointer: &mut ready },
+      //^^^^^^^^^^^^^^^^^^^^^^^^^^^ self: Pin<&mut Self>
+        &mut context,
+      //^^^^^^^^^^^^ cx
+    );
+    Future::consume(Box::new(Ready));
+                  //^^^^^^^^^^^^^^^ mut self: Box<Self>
 }
 "#,
         );
     }
 
     #[test]
-    fn shorten_iterator_chaining_hints() {
-        check_expect(
+    fn blanket_impl_method_param_hints() {
+        check_with_config(
             InlayHintsConfig {
-                parameter_hints: false,
-                type_hints: false,
-                chaining_hints: true,
-                max_length: None,
+                parameter_hints: true,
+                self_param_hints: true,
+                self_param_hints_only_when_ufcs: true,
+                type_hints: true,
+                ..DISABLED_CONFIG
             },
             r#"
-use core::iter;
+trait Named {
+    fn name(&self) -> u32;
+}
 
-struct MyIter;
+trait Greet {
+    fn greet(&self, greeting: u32, times: u32) -> u32;
+    fn greeter(greeting: u32) -> u32;
+}
 
-impl Iterator for MyIter {
-    type Item = ();
-    fn next(&mut self) -> Option<Self::Item> {
-        None
-    }
+impl<T: Named> Greet for T {
+    fn greet(&self, greeting: u32, times: u32) -> u32 { greeting * times }
+    fn greeter(greeting: u32) -> u32 { greeting }
+}
+
+struct Person;
+impl Named for Person {
+    fn name(&self) -> u32 { 0 }
 }
 
 fn main() {
-    let _x = MyIter.by_ref()
-        .take(5)
-        .by_ref()
-        .take(5)
-        .by_ref();
+    let res = Person.greet(
+      //^^^ u32
+        1,
+      //^ greeting
+        2,
+      //^ times
+    );
+    Greet::greet(
+        &Person,
+      //^^^^^^^ &self
+        3,
+      //^ greeting
+        4,
+      //^ times
+    );
+    <Person as Greet>::greeter(5);
+                             //^ greeting
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn hints_of_many_items_stay_in_source_order() {
+        let text = (0..64)
+            .map(|i| format!("fn f{}(amount: u32) {{ let x = {}u32; f{}(x); }}\n", i, i, i))
+            .collect::<String>();
+        let (analysis, file_id) = fixture::file(&text);
+        let hints = analysis.inlay_hints(file_id, &InlayHintsConfig::default()).unwrap();
+        assert_eq!(hints.len(), 64 * 2);
+        assert!(hints.windows(2).all(|w| w[0].range.start() < w[1].range.start()));
+    }
+
+    #[test]
+    fn question_mark_chain_hints() {
+        check_expect(
+            InlayHintsConfig { question_mark_chain_hints: true, ..DISABLED_CONFIG },
+            r#"
+use core::ops::ControlFlow;
+
+struct A { b: ControlFlow<(), B> }
+struct B { c: ControlFlow<(), C> }
+struct C { value: u32 }
+
+impl C {
+    fn get(&self) -> ControlFlow<(), u32> { ControlFlow::Continue(self.value) }
+}
+
+fn run(a: ControlFlow<(), A>, single: ControlFlow<(), C>) -> ControlFlow<(), u32> {
+    let value = a?.b?.c?.get()?;
+    let alone = single?.value;
+    ControlFlow::Continue(value + alone)
 }
 "#,
             expect![[r#"
                 [
                     InlayHint {
-                        range: 175..242,
+                        range: 317..319,
                         kind: ChainingHint,
-                        label: "impl Iterator<Item = ()>",
+                        label: [
+                            InlayHintLabelPart {
+                                text: "A",
+                                goto: FileRange {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    range: 37..38,
+                                },
+                            },
+                        ],
+                        tooltip: None,
+                        id: None,
+                        pad_left: true,
+                        pad_right: false,
+                        text_edit: None,
                     },
                     InlayHint {
-                        range: 175..225,
+                        range: 317..322,
                         kind: ChainingHint,
-                        label: "impl Iterator<Item = ()>",
+                        label: [
+                            InlayHintLabelPart {
+                                text: "B",
+                                goto: FileRange {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    range: 72..73,
+                                },
+                            },
+                        ],
+                        tooltip: None,
+                        id: None,
+                        pad_left: true,
+                        pad_right: false,
+                        text_edit: None,
                     },
                     InlayHint {
-                        range: 175..207,
+                        range: 317..325,
                         kind: ChainingHint,
-                        label: "impl Iterator<Item = ()>",
+                        label: [
+                            InlayHintLabelPart {
+                                text: "C",
+                                goto: FileRange {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    range: 107..108,
+                                },
+                            },
+                        ],
+                        tooltip: None,
+                        id: None,
+                        pad_left: true,
+                        pad_right: false,
+                        text_edit: None,
                     },
                     InlayHint {
-                        range: 175..190,
+                        range: 317..332,
+                        kind: ChainingHint,
+                        label: "u32",
+                        tooltip: None,
+                        id: None,
+                        pad_left: true,
+                        pad_right: false,
+                        text_edit: None,
+                    },
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn extended_temporary_lifetimes() {
+        check_with_config(
+            InlayHintsConfig { type_hints: true, show_lifetimes_in_hints: true, ..DISABLED_CONFIG },
+            r#"
+struct Counter { count: u32 }
+impl Counter {
+    fn next(&self) -> Option<&u32> { None }
+}
+fn make() -> Counter { Counter { count: 0 } }
+
+fn main() {
+    let counter = make();
+      //^^^^^^^ Counter
+    let next = &counter.next();
+      //^^^^ &'block Option<&u32>
+    let twice = &&make();
+      //^^^^^ &'block &'block Counter
+    let field = &make().count;
+      //^^^^^ &'block u32
+    let place = &counter;
+      //^^^^^ &Counter
+    let place_field = &counter.count;
+      //^^^^^^^^^^^ &u32
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generic_chaining_hints() {
+        check_expect(
+            InlayHintsConfig { chaining_hints: true, ..DISABLED_CONFIG },
+            r#"
+struct A<T>(T);
+struct B<T>(T);
+struct C<T>(T);
+struct X<T,R>(T, R);
+
+impl<T> A<T> {
+    fn new(t: T) -> Self { A(t) }
+    fn into_b(self) -> B<T> { B(self.0) }
+}
+impl<T> B<T> {
+    fn into_c(self) -> C<T> { C(self.0) }
+}
+fn main() {
+    let c = A::new(X(42, true))
+        .into_b()
+        .into_c();
+}
+"#,
+            expect![[r#"
+                [
+                    InlayHint {
+                        range: 247..266,
+                        kind: ChainingHint,
+                        label: [
+                            InlayHintLabelPart {
+                                text: "A",
+                                goto: FileRange {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    range: 8..9,
+                                },
+                            },
+                            "<",
+                            InlayHintLabelPart {
+                                text: "X",
+                                goto: FileRange {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    range: 56..57,
+                                },
+                            },
+                            "<i32, bool>>",
+                        ],
+                        tooltip: None,
+                        id: None,
+                        pad_left: true,
+                        pad_right: false,
+                        text_edit: None,
+                    },
+                    InlayHint {
+                        range: 247..284,
                         kind: ChainingHint,
-                        label: "&mut MyIter",
+                        label: [
+                            InlayHintLabelPart {
+                                text: "B",
+                                goto: FileRange {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    range: 24..25,
+                                },
+                            },
+                            "<",
+                            InlayHintLabelPart {
+                                text: "X",
+                                goto: FileRange {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    range: 56..57,
+                                },
+                            },
+                            "<i32, bool>>",
+                        ],
+                        tooltip: None,
+                        id: None,
+                        pad_left: true,
+                        pad_right: false,
+                        text_edit: None,
                     },
                 ]
             "#]],
         );
     }
+
+    #[test]
+    fn incomplete_for_no_hint() {
+        check(
+            r#"
+fn main() {
+    let data = &[1i32, 2, 3];
+      //^^^^ &[i32; _]
+    for i
+}"#,
+        );
+        check(
+            r#"
+pub struct Vec<T> {}
+
+impl<T> Vec<T> {
+    pub fn new() -> Self { Vec {} }
+    pub fn push(&mut self, t: T) {}
+}
+
+impl<T> IntoIterator for Vec<T> {
+    type Item=T;
+}
+
+fn main() {
+    let mut data = Vec::new();
+      //^^^^^^^^ Vec<&str>
+    data.push("foo");
+    for i in
+
+    println!("Unit expr");
+}
+
+//- /alloc.rs crate:alloc deps:core
+mod collections {
+    struct Vec<T> {}
+    impl<T> Vec<T> {
+        fn new() -> Self { Vec {} }
+        fn push(&mut self, t: T) { }
+    }
+    impl<T> IntoIterator for Vec<T> {
+        type Item=T;
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn complete_for_hint() {
+        check(
+            r#"
+pub struct Vec<T> {}
+
+impl<T> Vec<T> {
+    pub fn new() -> Self { Vec {} }
+    pub fn push(&mut self, t: T) {}
+}
+
+impl<T> IntoIterator for Vec<T> {
+    type Item=T;
+}
+
+fn main() {
+    let mut data = Vec::new();
+      //^^^^^^^^ Vec<&str>
+    data.push("foo");
+    for i in data {
+      //^ &str
+      let z = i;
+        //^ &str
+    }
+}
+//- /alloc.rs crate:alloc deps:core
+mod collections {
+    struct Vec<T> {}
+    impl<T> Vec<T> {
+        fn new() -> Self { Vec {} }
+        fn push(&mut self, t: T) { }
+    }
+    impl<T> IntoIterator for Vec<T> {
+        type Item=T;
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn multi_dyn_trait_bounds() {
+        check_with_config(
+            InlayHintsConfig { type_hints: true, ..DISABLED_CONFIG },
+            r#"
+pub struct Vec<T> {}
+
+impl<T> Vec<T> {
+    pub fn new() -> Self { Vec {} }
+}
+
+pub struct Box<T> {}
+
+fn vec<T>() -> Vec<T> { Vec::new() }
+
+trait Display {}
+trait Sync {}
+
+fn main() {
+    let _v = vec::<Box<&(dyn Display + Sync)>>();
+      //^^ Vec<Box<&(dyn Display + Sync)>>
+    let _v = vec::<Box<*const (dyn Display + Sync)>>();
+      //^^ Vec<Box<*const (dyn Display + Sync)>>
+    let _v = vec::<Box<dyn Display + Sync>>();
+      //^^ Vec<Box<dyn Display + Sync>>
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn shorten_iterator_hints() {
+        check_with_config(
+            InlayHintsConfig { type_hints: true, ..DISABLED_CONFIG },
+            r#"
+use core::iter;
+
+struct MyIter;
+
+impl Iterator for MyIter {
+    type Item = ();
+    fn next(&mut self) -> Option<Self::Item> {
+        None
+    }
+}
+
+fn main() {
+    let _x = MyIter;
+      //^^ MyIter
+    let _x = iter::repeat(0);
+      //^^ impl Iterator<Item = i32>
+    fn generic<T: Clone>(t: T) {
+        let _x = iter::repeat(t);
+          //^^ impl Iterator<Item = T>
+        let _chained = iter::repeat(t).take(10);
+          //^^^^^^^^ impl Iterator<Item = T>
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn shorten_std_reexported_iterator_hints() {
+        let ra_fixture = format!(
+            "{}\n{}",
+            r#"
+//- /main.rs crate:main deps:std
+use std::iter::repeat;
+
+fn main() {
+    let _x = repeat(0);
+      //^^ impl Iterator<Item = i32>
+    let _args = std::env::args();
+      //^^^^^ impl Iterator<Item = u8>
+}
+
+//- /std.rs crate:std deps:core
+pub use core::iter;
+
+pub mod env {
+    pub struct Args;
+    impl Iterator for Args {
+        type Item = u8;
+        fn next(&mut self) -> Option<u8> { None }
+    }
+    pub fn args() -> Args { Args }
+}
+"#,
+            FamousDefs::FIXTURE
+        );
+        let (analysis, file_id) = fixture::file(&ra_fixture);
+        let expected = extract_annotations(&analysis.file_text(file_id).unwrap());
+        let config = InlayHintsConfig { type_hints: true, ..DISABLED_CONFIG };
+        let inlay_hints = analysis.inlay_hints(file_id, &config).unwrap();
+        let actual =
+            inlay_hints.into_iter().map(|it| (it.range, it.label.to_string())).collect::<Vec<_>>();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn shorten_nested_result_hints() {
+        check_with_config(
+            InlayHintsConfig {
+                type_hints: true,
+                result_shortening: true,
+                max_length: Some(25),
+                ..DISABLED_CONFIG
+            },
+            r#"
+use core::result::Result;
+
+struct Error;
+struct Other;
+struct Vec<T>(T);
+
+fn nested() -> Result<Result<i32, Other>, Error> { loop {} }
+fn short() -> Result<Vec<i32>, Error> { loop {} }
+fn simple() -> Result<i32, Error> { loop {} }
+
+fn main() {
+    let nested = nested();
+      //^^^^^^ Result<Result<…>, Error>
+    let short = short();
+      //^^^^^ Result<Vec<i32>, Error>
+    let simple = simple();
+      //^^^^^^ Result<i32, Error>
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn hide_string_type_hints() {
+        let ra_fixture = format!(
+            "//- /main.rs crate:main deps:core,alloc\n{}\n{}",
+            r#"
+use alloc::{borrow::Cow, string::String, vec::Vec};
+
+fn string() -> String { loop {} }
+fn strings() -> Vec<String> { loop {} }
+fn cow() -> Cow<'static, str> { loop {} }
+fn lookalike() -> alloc::fmt::String { loop {} }
+fn slice_mut() -> &'static mut str { loop {} }
+
+fn main() {
+    let owned = string();
+    let borrowed = &owned;
+    let mut other = string();
+    let borrowed_mut = &mut other;
+    let slice = "text";
+    let slice_mut = slice_mut();
+      //^^^^^^^^^ &mut str
+    let strings = strings();
+      //^^^^^^^ Vec<String>
+    let cow = cow();
+      //^^^ Cow<str>
+    let lookalike = lookalike();
+      //^^^^^^^^^ String
+}
+
+//- /alloc.rs crate:alloc deps:core
+pub mod string {
+    pub struct String;
+}
+pub mod fmt {
+    pub struct String;
+}
+pub mod vec {
+    pub struct Vec<T>(T);
+}
+pub mod borrow {
+    pub enum Cow<'a, B: ?Sized> {
+        Borrowed(&'a B),
+    }
+}
+"#,
+            FamousDefs::FIXTURE
+        );
+        let config =
+            InlayHintsConfig { type_hints: true, hide_string_type_hints: true, ..DISABLED_CONFIG };
+        let (analysis, file_id) = fixture::file(&ra_fixture);
+        let expected = extract_annotations(&analysis.file_text(file_id).unwrap());
+        let inlay_hints = analysis.inlay_hints(file_id, &config).unwrap();
+        let actual =
+            inlay_hints.into_iter().map(|it| (it.range, it.label.to_string())).collect::<Vec<_>>();
+        assert_eq!(expected, actual, "\nExpected:\n{:#?}\n\nActual:\n{:#?}", expected, actual);
+    }
+
+    #[test]
+    fn max_generic_args_collapse_the_rest() {
+        check_with_config(
+            InlayHintsConfig { type_hints: true, max_generic_args: Some(2), ..DISABLED_CONFIG },
+            r#"
+struct Tuple<A, B, C, D, E>(A, B, C, D, E);
+struct Pair<A, B>(A, B);
+
+fn main() {
+    let tuple = Tuple(1, 2u8, 3u16, 4u32, 5u64);
+      //^^^^^ Tuple<i32, u8, …>
+    let pair = Pair(tuple, 1);
+      //^^^^ Pair<Tuple<i32, u8, …>, i32>
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn iterator_hints_fit_max_length() {
+        let fixture = r#"
+use core::iter;
+
+struct LongItemName;
+
+fn main() {
+    let _x = iter::repeat(LongItemName);
+}
+"#;
+        let ra_fixture =
+            format!("//- /main.rs crate:main deps:core\n{}\n{}", fixture, FamousDefs::FIXTURE);
+        let (analysis, file_id) = fixture::file(&ra_fixture);
+        let label_with_max_length = |max_length| {
+            let config = InlayHintsConfig {
+                type_hints: true,
+                max_length: Some(max_length),
+                ..DISABLED_CONFIG
+            };
+            let hints = analysis.inlay_hints(file_id, &config).unwrap();
+            assert_eq!(hints.len(), 1);
+            hints[0].label.to_string()
+        };
+
+        assert_eq!(label_with_max_length(4), "imp…");
+        assert_eq!(label_with_max_length(10), "impl Iter…");
+        assert_eq!(label_with_max_length(22), "impl Iterator<Item = …");
+        assert_eq!(label_with_max_length(23), "impl Iterator<Item = …>");
+        assert_eq!(label_with_max_length(26), "impl Iterator<Item = Lon…>");
+        assert_eq!(label_with_max_length(40), "impl Iterator<Item = LongItemName>");
+        for max_length in 0..40 {
+            assert!(label_with_max_length(max_length).chars().count() <= max_length);
+        }
+    }
+
+    #[test]
+    fn question_mark_on_collected_result() {
+        check_with_config(
+            InlayHintsConfig { type_hints: true, ..DISABLED_CONFIG },
+            r#"
+use core::ops::Try;
+
+enum Result<T, E> { Ok(T), Err(E) }
+impl<T, E> Try for Result<T, E> {
+    type Ok = T;
+    type Error = E;
+}
+
+struct Vec<T> { item: T }
+struct ParseError;
+struct Number;
+
+trait FromIterator<A> {
+    fn from_iter<I: Iterator<Item = A>>(iter: I) -> Self;
+}
+impl<T> FromIterator<T> for Vec<T> {
+    fn from_iter<I: Iterator<Item = T>>(iter: I) -> Self { loop {} }
+}
+impl<A, E, V: FromIterator<A>> FromIterator<Result<A, E>> for Result<V, E> {
+    fn from_iter<I: Iterator<Item = Result<A, E>>>(iter: I) -> Self { loop {} }
+}
+
+trait Collect: Iterator + Sized {
+    fn collect<B: FromIterator<Self::Item>>(self) -> B { B::from_iter(self) }
+}
+impl<I: Iterator> Collect for I {}
+
+struct Items;
+impl Iterator for Items {
+    type Item = Result<Number, ParseError>;
+    fn next(&mut self) -> Option<Self::Item> { None }
+}
+
+fn parse_all(items: Items, more: Items) -> Result<(), ParseError> {
+    let numbers = items.collect::<Result<Vec<_>, ParseError>>()?;
+      //^^^^^^^ Vec<Number>                  ^ Number
+    let annotated: Vec<_> = more.collect::<Result<_, ParseError>>()?;
+      //^^^^^^^^^ Vec<Number>                     ^ Vec<Number>
+    let inferred: Result<Vec<_>, _> = Items.collect();
+      //^^^^^^^^ Result<Vec<Number>, ParseError>
+    let unwrapped = inferred?;
+      //^^^^^^^^^ Vec<Number>
+    let explicit: Vec<Number> = unwrapped;
+    Result::Ok(())
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn question_mark_conversion_hints() {
+        check_with_config(
+            InlayHintsConfig { question_mark_conversion_hints: true, ..DISABLED_CONFIG },
+            r#"
+use core::{convert::From, ops::Try};
+
+enum Result<T, E> { Ok(T), Err(E) }
+impl<T, E> Try for Result<T, E> {
+    type Ok = T;
+    type Error = E;
+}
+
+trait Error {}
+struct Box<T: ?Sized> { inner: *const T }
+impl<E: Error> From<E> for Box<dyn Error> {
+    fn from(err: E) -> Self { loop {} }
+}
+
+struct ParseError;
+impl Error for ParseError {}
+
+fn parse() -> Result<u32, ParseError> { Result::Ok(0) }
+
+fn propagate() -> Result<u32, ParseError> {
+    let value = parse()?;
+    Result::Ok(value)
+}
+
+fn boxed() -> Result<u32, Box<dyn Error>> {
+    let value = parse()?;
+                     //^ as Box<dyn Error>
+    let in_closure = || -> Result<u32, ParseError> { parse() };
+    let same = in_closure()?;
+                         //^ as Box<dyn Error>
+    Result::Ok(value)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn lazy_question_mark_conversion_hints() {
+        check_lazy_labels(
+            InlayHintsConfig { question_mark_conversion_hints: true, ..DISABLED_CONFIG },
+            r#"
+use core::{convert::From, ops::Try};
+
+enum Result<T, E> { Ok(T), Err(E) }
+impl<T, E> Try for Result<T, E> {
+    type Ok = T;
+    type Error = E;
+}
+
+trait Error {}
+struct Box<T: ?Sized> { inner: *const T }
+impl<E: Error> From<E> for Box<dyn Error> {
+    fn from(err: E) -> Self { loop {} }
+}
+
+struct ParseError;
+impl Error for ParseError {}
+
+fn parse() -> Result<u32, ParseError> { Result::Ok(0) }
+
+fn boxed() -> Result<u32, Box<dyn Error>> {
+    let value = parse()?;
+    Result::Ok(value)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn shorten_future_hints() {
+        check_with_config(
+            InlayHintsConfig { type_hints: true, ..DISABLED_CONFIG },
+            r#"
+use core::future;
+
+struct Local;
+
+fn main() {
+    let _x = future::ready(0);
+      //^^ impl Future<Output = i32>
+    let _y = &future::ready(Local);
+      //^^ &Ready<Local>
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn type_hint_text_edits() {
+        let (analysis, file_id) = fixture::file(
+            r#"
+struct Wrapper<T>(T);
+fn main() {
+    let mut number = 1;
+    let wrapped = Wrapper(Wrapper(number));
+    let (left, right) = (1, 2);
+    let annotated: Wrapper<_> = Wrapper(1);
+    let closure = |param| param;
+    closure(1u16);
+}
+"#,
+        );
+        let config = InlayHintsConfig { type_hints: true, max_length: Some(10), ..DISABLED_CONFIG };
+        let mut edit = TextEdit::builder().finish();
+        for hint in analysis.inlay_hints(file_id, &config).unwrap() {
+            if let Some(hint_edit) = hint.text_edit {
+                edit.union(hint_edit).unwrap();
+            }
+        }
+        let mut text = analysis.file_text(file_id).unwrap().to_string();
+        edit.apply(&mut text);
+        expect![[r#"
+            struct Wrapper<T>(T);
+            fn main() {
+                let mut number: i32 = 1;
+                let wrapped: Wrapper<Wrapper<i32>> = Wrapper(Wrapper(number));
+                let (left, right) = (1, 2);
+                let annotated: Wrapper<_> = Wrapper(1);
+                let closure = |param: u16| param;
+                closure(1u16);
+            }
+        "#]]
+        .assert_eq(&text);
+    }
+
+    #[test]
+    fn concrete_hints_inside_impl_trait_returning_fn() {
+        check_with_config(
+            InlayHintsConfig { type_hints: true, ..DISABLED_CONFIG },
+            r#"
+struct Counter { count: i32 }
+impl Iterator for Counter {
+    type Item = i32;
+    fn next(&mut self) -> Option<i32> { None }
+}
+
+fn counter(start: i32) -> impl Iterator<Item = i32> {
+    let count = start * 2;
+      //^^^^^ i32
+    let counter = Counter { count };
+      //^^^^^^^ Counter
+    let pair = (counter, count);
+      //^^^^ (Counter, i32)
+    pair.0
+}
+
+fn main() {
+    let opaque = counter(1);
+      //^^^^^^ impl Iterator<Item = i32>
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_type_hint_text_edits_for_impl_trait() {
+        let (analysis, file_id) = fixture::file(
+            r#"
+trait Iterator { type Item; }
+struct Counter;
+impl Iterator for Counter { type Item = i32; }
+struct Wrapper<T>(T);
+fn make_iter() -> impl Iterator<Item = i32> { Counter }
+fn main() {
+    let it = make_iter();
+    let wrapped = Wrapper(make_iter());
+    let counter = Counter;
+}
+"#,
+        );
+        let config = InlayHintsConfig { type_hints: true, ..DISABLED_CONFIG };
+        let mut edit = TextEdit::builder().finish();
+        for hint in analysis.inlay_hints(file_id, &config).unwrap() {
+            if let Some(hint_edit) = hint.text_edit {
+                edit.union(hint_edit).unwrap();
+            }
+        }
+        let mut text = analysis.file_text(file_id).unwrap().to_string();
+        edit.apply(&mut text);
+        expect![[r#"
+            trait Iterator { type Item; }
+            struct Counter;
+            impl Iterator for Counter { type Item = i32; }
+            struct Wrapper<T>(T);
+            fn make_iter() -> impl Iterator<Item = i32> { Counter }
+            fn main() {
+                let it = make_iter();
+                let wrapped = Wrapper(make_iter());
+                let counter: Counter = Counter;
+            }
+        "#]]
+        .assert_eq(&text);
+    }
+
+    #[test]
+    fn iterator_next_normalizes_item_type() {
+        check_with_config(
+            InlayHintsConfig { type_hints: true, ..DISABLED_CONFIG },
+            r#"
+use core::iter;
+
+fn bounded<I: Iterator<Item = u8>>(mut bytes: I) {
+    let byte = bytes.next();
+      //^^^^ Option<u8>
+}
+
+fn unbounded<I: Iterator>(mut items: I) {
+    let item = items.next();
+      //^^^^ Option<I::Item>
+}
+
+fn main() {
+    let mut numbers = iter::repeat(1);
+      //^^^^^^^^^^^ impl Iterator<Item = i32>
+    let number = numbers.next();
+      //^^^^^^ Option<i32>
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn index_output_hints() {
+        check_with_config(
+            InlayHintsConfig { type_hints: true, index_output_hints: true, ..DISABLED_CONFIG },
+            r#"
+use core::ops::Index;
+
+struct HashMap<K, V> { key: K, value: V }
+impl<K, V> Index<&K> for HashMap<K, V> {
+    type Output = V;
+    fn index(&self, key: &K) -> &V { &self.value }
+}
+
+struct Key;
+struct Value;
+
+fn take(value: &Value) {}
+
+fn main(map: HashMap<Key, Value>, values: [Value; 2]) {
+    take(&map[&Key]);
+        //^^^^^^^^^ Value
+    let value = map[&Key];
+      //^^^^^ Value
+    take(&values[0]);
+        //^^^^^^^^^ Value
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn turbofish_placeholder_hints() {
+        check_with_config(
+            InlayHintsConfig { type_hints: true, ..DISABLED_CONFIG },
+            r#"
+struct Vec<T> { item: T }
+struct Pair<A, B> { a: A, b: B }
+
+trait FromIterator<A> {}
+impl<T> FromIterator<T> for Vec<T> {}
+impl<A, B> FromIterator<(A, B)> for Pair<Vec<A>, Vec<B>> {}
+
+trait Collect: Iterator + Sized {
+    fn collect<B: FromIterator<Self::Item>>(self) -> B { loop {} }
+    fn count_as<N>(self) -> usize { 0 }
+}
+impl<I: Iterator> Collect for I {}
+
+struct Numbers;
+impl Iterator for Numbers {
+    type Item = (u8, &'static str);
+    fn next(&mut self) -> Option<Self::Item> { None }
+}
+
+fn main() {
+    Numbers.collect::<Vec<_>>();
+                        //^ (u8, &str)
+    Numbers.collect::<Pair<Vec<_>, Vec<&_>>>();
+                             //^ u8     ^ str
+    Numbers.count_as::<_>();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn shorten_iterator_chaining_hints() {
+        check_expect(
+            InlayHintsConfig { chaining_hints: true, ..DISABLED_CONFIG },
+            r#"
+use core::iter;
+
+struct MyIter;
+
+impl Iterator for MyIter {
+    type Item = ();
+    fn next(&mut self) -> Option<Self::Item> {
+        None
+    }
+}
+
+fn main() {
+    let _x = MyIter.by_ref()
+        .take(5)
+        .by_ref()
+        .take(5)
+        .by_ref();
+}
+"#,
+            expect![[r#"
+                [
+                    InlayHint {
+                        range: 175..190,
+                        kind: ChainingHint,
+                        label: [
+                            "&mut ",
+                            InlayHintLabelPart {
+                                text: "MyIter",
+                                goto: FileRange {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    range: 25..31,
+                                },
+                            },
+                        ],
+                        tooltip: None,
+                        id: None,
+                        pad_left: true,
+                        pad_right: false,
+                        text_edit: None,
+                    },
+                    InlayHint {
+                        range: 175..207,
+                        kind: ChainingHint,
+                        label: "impl Iterator<Item = ()>",
+                        tooltip: None,
+                        id: None,
+                        pad_left: true,
+                        pad_right: false,
+                        text_edit: None,
+                    },
+                    InlayHint {
+                        range: 175..225,
+                        kind: ChainingHint,
+                        label: "impl Iterator<Item = ()>",
+                        tooltip: None,
+                        id: None,
+                        pad_left: true,
+                        pad_right: false,
+                        text_edit: None,
+                    },
+                    InlayHint {
+                        range: 175..242,
+                        kind: ChainingHint,
+                        label: "impl Iterator<Item = ()>",
+                        tooltip: None,
+                        id: None,
+                        pad_left: true,
+                        pad_right: false,
+                        text_edit: None,
+                    },
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn dedup_adjacent_chaining_hints() {
+        check_expect(
+            InlayHintsConfig {
+                chaining_hints: true,
+                dedup_adjacent_chaining_hints: true,
+                ..DISABLED_CONFIG
+            },
+            r#"
+use core::iter;
+
+struct MyIter;
+
+impl Iterator for MyIter {
+    type Item = ();
+    fn next(&mut self) -> Option<Self::Item> {
+        None
+    }
+}
+
+fn main() {
+    let _x = MyIter.by_ref()
+        .take(5)
+        .by_ref()
+        .take(5)
+        .by_ref();
+}
+"#,
+            expect![[r#"
+                [
+                    InlayHint {
+                        range: 175..190,
+                        kind: ChainingHint,
+                        label: [
+                            "&mut ",
+                            InlayHintLabelPart {
+                                text: "MyIter",
+                                goto: FileRange {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    range: 25..31,
+                                },
+                            },
+                        ],
+                        tooltip: None,
+                        id: None,
+                        pad_left: true,
+                        pad_right: false,
+                        text_edit: None,
+                    },
+                    InlayHint {
+                        range: 175..207,
+                        kind: ChainingHint,
+                        label: "impl Iterator<Item = ()>",
+                        tooltip: None,
+                        id: None,
+                        pad_left: true,
+                        pad_right: false,
+                        text_edit: None,
+                    },
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn chaining_hints_on_change_only() {
+        check_expect(
+            InlayHintsConfig {
+                chaining_hints: true,
+                chaining_hints_on_change_only: true,
+                ..DISABLED_CONFIG
+            },
+            r#"
+struct MyIter;
+
+impl Iterator for MyIter {
+    type Item = ();
+    fn next(&mut self) -> Option<Self::Item> {
+        None
+    }
+}
+
+fn main() {
+    let _x = MyIter
+        .by_ref()
+        .by_ref()
+        .by_ref()
+        .take(5)
+        .by_ref();
+}
+"#,
+            expect![[r#"
+                [
+                    InlayHint {
+                        range: 158..182,
+                        kind: ChainingHint,
+                        label: [
+                            "&mut ",
+                            InlayHintLabelPart {
+                                text: "MyIter",
+                                goto: FileRange {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    range: 8..14,
+                                },
+                            },
+                        ],
+                        tooltip: None,
+                        id: None,
+                        pad_left: true,
+                        pad_right: false,
+                        text_edit: None,
+                    },
+                    InlayHint {
+                        range: 158..235,
+                        kind: ChainingHint,
+                        label: "impl Iterator<Item = ()>",
+                        tooltip: None,
+                        id: None,
+                        pad_left: true,
+                        pad_right: false,
+                        text_edit: None,
+                    },
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn chaining_hints_on_record_literals() {
+        check_expect(
+            InlayHintsConfig {
+                chaining_hints: true,
+                chaining_hints_on_record_literals: true,
+                ..DISABLED_CONFIG
+            },
+            r#"
+struct Point { x: i32 }
+struct Empty {}
+
+impl Point { fn into_empty(self) -> Empty { Empty {} } }
+impl Empty { fn into_point(self) -> Point { Point { x: 0 } } }
+
+fn main() {
+    let _p = Point { x: 1 }
+        .into_empty();
+    let _e = Empty {}
+        .into_point();
+}
+"#,
+            expect![[r#"
+                [
+                    InlayHint {
+                        range: 188..202,
+                        kind: ChainingHint,
+                        label: [
+                            InlayHintLabelPart {
+                                text: "Point",
+                                goto: FileRange {
+                                    file_id: FileId(
+                                        0,
+                                    ),
+                                    range: 8..13,
+                                },
+                            },
+                        ],
+                        tooltip: None,
+                        id: None,
+                        pad_left: true,
+                        pad_right: false,
+                        text_edit: None,
+                    },
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn arg_order_mismatch_hints() {
+        check_with_kinds(
+            InlayHintsConfig { arg_order_mismatch_hints: true, ..DISABLED_CONFIG },
+            r#"
+struct Point;
+impl Point {
+    fn offset(&self, dx: i32, dy: i32) {}
+}
+
+fn area(width: u32, height: u32) -> u32 { width * height }
+
+fn main() {
+    let (width, height, depth) = (1, 2, 3);
+    area(
+        height,
+      //^^^^^^ arg_order: width, not height
+        width,
+      //^^^^^ arg_order: height, not width
+    );
+    area(width, height);
+    area(depth, width);
+              //^^^^^ arg_order: height, not width
+    let (dx, dy) = (0, 0);
+    Point.offset(
+        &dy,
+      //^^^ arg_order: dx, not dy
+        dx,
+      //^^ arg_order: dy, not dx
+    );
+}"#,
+        );
+    }
+
+    #[test]
+    fn unawaited_async_fn_call_hints() {
+        check_with_config(
+            InlayHintsConfig { type_hints: true, ..DISABLED_CONFIG },
+            r#"
+struct Local;
+
+async fn make() -> Local { Local }
+
+async fn run() {
+    let fut = make();
+      //^^^ impl Future<Output = Local>
+    let awaited = make().await;
+      //^^^^^^^ Local
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn tuple_constructor_hints() {
+        check_with_config(
+            InlayHintsConfig {
+                parameter_hints: true,
+                tuple_constructor_hints: true,
+                ..DISABLED_CONFIG
+            },
+            r#"
+struct Pair(i32, bool);
+struct Wrapper(u32);
+enum Shape {
+    Rect(u32, u32),
+    Circle(u32),
+}
+
+fn main() {
+    let pair = Pair(
+        1,
+      //^ .0
+        true,
+      //^^^^ .1
+    );
+    let rect = Shape::Rect(
+        2,
+      //^ .0
+        3,
+      //^ .1
+    );
+    let circle = Shape::Circle(4);
+                             //^ .0
+    let wrapper = Wrapper(5);
+                        //^ .0
+}"#,
+        );
+    }
+
+    #[test]
+    fn closure_capture_hints() {
+        // Each capture gets its own hint, all of them before the parameters.
+        check_with_config(
+            InlayHintsConfig {
+                closure_capture_hints: true,
+                combine_colocated_hints: true,
+                ..DISABLED_CONFIG
+            },
+            r#"
+#[lang = "copy"]
+trait Copy {}
+impl Copy for i32 {}
+
+struct Token;
+impl Token {
+    fn peek(&self) {}
+    fn bump(&mut self) {}
+    fn finish(self) {}
+}
+
+fn consume(token: Token) {}
+
+fn main() {
+    let (read, mut write, moved, finished, count) = (Token, Token, Token, Token, 1);
+    let f = |x: i32| {
+          //^^^^^^^^ &read &mut write move moved move finished &count
+        read.peek();
+        write.bump();
+        consume(moved);
+        finished.finish();
+        let _ = count + x;
+    };
+    let g = move || read.peek();
+               //^^ read
+    let h = || {
+        let read = Token;
+        read.finish();
+    };
+}"#,
+        );
+    }
+
+    #[test]
+    fn closure_params_from_expected_type() {
+        check_with_config(
+            InlayHintsConfig { type_hints: true, ..DISABLED_CONFIG },
+            r#"
+#[lang = "fn_once"]
+trait FnOnce<Args> {
+    type Output;
+}
+
+fn takes<F: FnOnce(u8)>(f: F) {}
+
+fn main() {
+    let f: fn(i32) = |x| {};
+                    //^ i32
+    let g: fn(i32, &str) -> bool = |a,
+                                  //^ i32
+                                    b| true;
+                                  //^ &str
+    takes(|y| {});
+         //^ u8
+    let h =
+      //^ |…| -> ()
+        |z| {};
+       //^ u16
+    h(1u16);
+}"#,
+        );
+    }
+
+    #[test]
+    fn any_downcast_hints() {
+        check_with_config(
+            InlayHintsConfig { type_hints: true, ..DISABLED_CONFIG },
+            r#"
+trait Any {}
+impl<T> Any for T {}
+impl dyn Any {
+    fn downcast_ref<T: Any>(&self) -> Option<&T> { loop {} }
+    fn downcast_mut<T: Any>(&mut self) -> Option<&mut T> { loop {} }
+}
+
+struct Concrete;
+
+fn inspect(any: &dyn Any, any_mut: &mut dyn Any) {
+    let by_ref = any.downcast_ref::<Concrete>();
+      //^^^^^^ Option<&Concrete>
+    let by_mut = any_mut.downcast_mut::<Concrete>();
+      //^^^^^^ Option<&mut Concrete>
+}"#,
+        );
+    }
+
+    #[test]
+    fn if_let_chain_with_boolean_condition() {
+        check_with_config(
+            InlayHintsConfig { type_hints: true, ..DISABLED_CONFIG },
+            r#"
+struct Test { b: u8 }
+
+fn main(a: Option<Test>, b: u8) {
+    if let Some(x) = a && b > 0 && x.b > b {
+              //^ Test
+        let y = x;
+          //^ Test
+    }
+    while let Some(x) = a && x.b > b {
+                 //^ Test
+        let y = x;
+          //^ Test
+    }
+}"#,
+        );
+    }
+
+    #[test]
+    fn ufcs_trait_method_param_hints() {
+        check_with_config(
+            InlayHintsConfig { parameter_hints: true, self_param_hints: true, ..DISABLED_CONFIG },
+            r#"
+trait Mapper {
+    fn map<F>(self, f: F) -> Self;
+}
+
+struct Items;
+impl Mapper for Items {
+    fn map<F>(self, f: F) -> Self { self }
+}
+
+fn main() {
+    let items = Items;
+    Mapper::map(
+        items,
+      //^^^^^ self
+        0,
+      //^ f
+    );
+    let repeat = core::iter::repeat(());
+                                  //^^ elt
+    Iterator::take(
+        repeat,
+      //^^^^^^ self
+        5,
+      //^ n
+    );
+}"#,
+        );
+    }
+
+    #[test]
+    fn truncation_policies() {
+        let (analysis, file_id) = fixture::file(
+            r#"
+struct Outer<A, B>(A, B);
+struct Middle<A, B>(A, B);
+struct Inner<A>(A);
+
+fn main() {
+    let nested = Outer(Middle(Inner(0u8), 0u16), 0u32);
+}
+"#,
+        );
+        let label = |truncation_policy| {
+            let config = InlayHintsConfig {
+                type_hints: true,
+                max_length: Some(21),
+                truncation_policy,
+                ..DISABLED_CONFIG
+            };
+            let hints = analysis.inlay_hints(file_id, &config).unwrap();
+            assert_eq!(hints.len(), 1);
+            hints[0].label.to_string()
+        };
+
+        // The char budget only stops the rendering of further arguments.
+        assert_eq!(label(TruncationPolicy::Chars), "Outer<Middle<Inner<u8>, …>, …>");
+        assert_eq!(label(TruncationPolicy::Delimiters), "Outer<Middle<…>>");
+        assert_eq!(label(TruncationPolicy::Depth), "Outer<Middle<…>, u32>");
+    }
+
+    #[test]
+    fn hide_hints_duplicating_turbofish() {
+        check_with_config(
+            InlayHintsConfig { type_hints: true, ..DISABLED_CONFIG },
+            r#"
+struct Vec<T>(T);
+impl<T> Vec<T> {
+    fn new() -> Self { loop {} }
+}
+struct Pair<A, B>(A, B);
+impl<A, B> Pair<A, B> {
+    fn new() -> Self { loop {} }
+}
+struct Text;
+impl Text {
+    fn parse<T>(&self) -> T { loop {} }
+}
+
+fn main() {
+    let spelled = Vec::<i32>::new();
+    let nested = Vec::<Vec<u8>>::new();
+    let pair = Pair::<i32,   u8>::new();
+    let partial = Vec::<_>::new();
+      //^^^^^^^ Vec<()>
+    let mut inferred = Vec::new();
+      //^^^^^^^^^^^^ Vec<u16>
+    inferred = Vec::<u16>::new();
+    let parsed = Text.parse::<u32>();
+    let wrapped = Text.parse::<Vec<_>>();
+      //^^^^^^^ Vec<i64>           ^ i64
+    let _: Vec<i64> = wrapped;
+    partial.0 = ();
+}"#,
+        );
+    }
+
+    #[test]
+    fn for_loop_over_enumerated_iterator() {
+        check_with_config(
+            InlayHintsConfig { type_hints: true, ..DISABLED_CONFIG },
+            r#"
+struct Item;
+
+struct Items;
+impl Items {
+    fn iter(&self) -> Iter<'_> { loop {} }
+}
+
+struct Iter<'a>(&'a Items);
+impl<'a> Iterator for Iter<'a> {
+    type Item = &'a Item;
+    fn next(&mut self) -> Option<&'a Item> { None }
+}
+
+fn main(items: Items) {
+    for (
+        i,
+      //^ usize
+        x,
+      //^ &Item
+    ) in items.iter().enumerate()
+    {}
+}"#,
+        );
+    }
+
+    #[test]
+    fn hide_hints_for_obvious_constructors() {
+        check_with_config(
+            InlayHintsConfig { hide_hints_for_obvious_constructors: true, ..Default::default() },
+            r#"
+struct Foo;
+impl Foo {
+    fn new() -> Foo { Foo }
+    fn default() -> Foo { Foo }
+    fn maybe() -> Option<Foo> { None }
+}
+struct Test<K> { k: K }
+impl<K> Test<K> {
+    fn new(k: K) -> Test<K> { Test { k } }
+}
+enum Option<T> { None, Some(T) }
+use Option::*;
+
+fn main() {
+    let literal = Foo {};
+    let new = Foo::new();
+    let default = Foo::default();
+    let record = Test { k: 33 };
+      //^^^^^^ Test<i32>
+    let generic = Test::new(33);
+      //^^^^^^^ Test<i32>
+    let spelled = Test::<i32>::new(33);
+    let other = Foo::maybe();
+      //^^^^^ Option<Foo>
+}"#,
+        );
+    }
+
+    #[test]
+    fn self_param_hints_on_method_call_receiver() {
+        check_with_config(
+            InlayHintsConfig { parameter_hints: true, self_param_hints: true, ..DISABLED_CONFIG },
+            r#"
+struct Type;
+impl Type {
+    fn method(&self) {}
+    fn consume(self) {}
+}
+
+fn main() {
+    let x = Type;
+    Type::method(&x);
+               //^^ &self
+    x.method();
+  //^ &self
+    x.consume();
+  //^ self
+}"#,
+        );
+    }
+
+    #[test]
+    fn self_param_hints_only_when_ufcs() {
+        check_with_config(
+            InlayHintsConfig {
+                parameter_hints: true,
+                self_param_hints: true,
+                self_param_hints_only_when_ufcs: true,
+                ..DISABLED_CONFIG
+            },
+            r#"
+struct Type;
+impl Type {
+    fn method(&self) {}
+    fn consume(self) {}
+}
+
+fn main() {
+    let x = Type;
+    Type::method(&x);
+               //^^ &self
+    x.method();
+    x.consume();
+}"#,
+        );
+    }
+
+    #[test]
+    fn nested_if_let_binding_hints() {
+        check_with_config(
+            InlayHintsConfig { type_hints: true, ..DISABLED_CONFIG },
+            r#"
+enum Option<T> { None, Some(T) }
+use Option::*;
+
+enum Level { Top, Leaf }
+
+fn main() {
+    let outer = Some(Some(Some(Level::Leaf)));
+      //^^^^^ Option<Option<Option<Level>>>
+    if let Some(middle) = outer {
+              //^^^^^^ Option<Option<Level>>
+        if let Some(inner) = middle {
+                  //^^^^^ Option<Level>
+            if let Some(Leaf) = inner {
+                let copy = Leaf;
+                  //^^^^ Level
+                while let Some(again) = Some(copy) {
+                             //^^^^^ Level
+                    let found = again;
+                      //^^^^^ Level
+                }
+            }
+        }
+    }
+}"#,
+        );
+    }
+
+    #[test]
+    fn inlay_hint_for_expr_at() {
+        let check = |ra_fixture: &str, expected: Option<&str>| {
+            let (analysis, position) = fixture::position(ra_fixture);
+            let hint = analysis
+                .inlay_hint_for_expr_at(position, &InlayHintsConfig::default())
+                .unwrap()
+                .map(|hint| hint.label.to_string());
+            assert_eq!(hint.as_deref(), expected);
+        };
+        let fixture = r#"
+struct Wrapper(u32);
+impl Wrapper {
+    fn get(&self) -> u32 { self.0 }
+}
+
+f<|>n main() {
+    let wrapper = Wrapper(1);
+    let pair = (wrapper.get() as u64, wrapper);
+}"#;
+        check(fixture, None);
+        let at = |needle: &str| {
+            fixture.replace("<|>", "").replacen(needle, &format!("<|>{}", needle), 1)
+        };
+        check(&at("(wrapper.get"), Some("(u64, Wrapper)"));
+        check(&at("wrapper.get"), Some("Wrapper"));
+        check(&at("get() as"), Some("u32"));
+        check(&at("as u64"), Some("u64"));
+        check(&at("Wrapper(1)"), Some("Wrapper(u32) -> Wrapper"));
+        check(&at("1)"), Some("u32"));
+    }
+
+    #[test]
+    fn for_loop_destructuring_hints() {
+        check_with_config(
+            InlayHintsConfig { type_hints: true, ..DISABLED_CONFIG },
+            r#"
+struct Key;
+struct Pair { a: u8, b: u16 }
+
+struct Map;
+impl IntoIterator for Map {
+    type Item = (Key, Pair);
+    type IntoIter = Entries;
+    fn into_iter(self) -> Entries { loop {} }
+}
+
+struct Entries;
+impl Iterator for Entries {
+    type Item = (Key, Pair);
+    fn next(&mut self) -> Option<(Key, Pair)> { None }
+}
+
+fn main(map: Map, entries: Entries) {
+    for (
+        k,
+      //^ Key
+        v,
+      //^ Pair
+    ) in map
+    {}
+    for (
+        i,
+      //^ usize
+        (
+            key,
+          //^^^ Key
+            Pair {
+                a,
+              //^ u8
+                b: renamed,
+                 //^^^^^^^ u16
+            },
+        ),
+    ) in entries.enumerate()
+    {}
+    for (x, y) in unknown() {}
+    for (x, y) in () {}
+}"#,
+        );
+    }
+
+    #[test]
+    fn or_pattern_bindings_hinted_once() {
+        check_with_config(
+            InlayHintsConfig { type_hints: true, ..DISABLED_CONFIG },
+            r#"
+enum Shape { Circle(u32), Square(u32), Line(u8, u8) }
+
+fn main(shape: Shape) {
+    match shape {
+        Shape::Circle(size) | Shape::Square(size) => {}
+                    //^^^^ u32
+        Shape::Line(
+            a,
+          //^ u8
+            b,
+          //^ u8
+        ) => {}
+    }
+    let (Shape::Circle(radius) | Shape::Square(radius)) = shape;
+                     //^^^^^^ u32
+}"#,
+        );
+    }
+
+    #[test]
+    fn slice_pattern_binding_hints() {
+        check_with_config(
+            InlayHintsConfig { type_hints: true, ..DISABLED_CONFIG },
+            r#"
+fn main(items: &[u32], array: [u8; 3]) {
+    match items {
+        [] => {}
+        [
+            head,
+          //^^^^ &u32
+            tail @ ..,
+          //^^^^ &[u32]
+        ] => {}
+    }
+    let [
+        first,
+      //^^^^^ u8
+        rest @ ..,
+      //^^^^ [u8; _]
+    ] = array;
+    if let [
+        only @ 1..=9,
+      //^^^^ &u32
+    ] = items
+    {}
+}
+
+#[lang = "owned_box"]
+struct Box<T>(T);
+
+fn unbox(boxed: Box<(u16, Box<i8>)>) {
+    let box (
+        value,
+      //^^^^^ u16
+        box inner,
+          //^^^^^ i8
+    ) = boxed;
+}"#,
+        );
+    }
+
+    #[test]
+    fn render_templates_wrap_labels_by_kind() {
+        let (analysis, file_id) = fixture::file(
+            r#"
+struct A(B);
+impl A { fn into_b(self) -> B { self.0 } }
+struct B;
+impl B { fn into_a(self) -> A { A(self) } }
+fn make(count: i32, flag: bool) -> A { A(B) }
+fn main() {
+    let a = make(1, true)
+        .into_b()
+        .into_a();
+}
+"#,
+        );
+        let template = |prefix: &str, suffix: &str| HintTemplate {
+            prefix: prefix.to_string(),
+            suffix: suffix.to_string(),
+        };
+        let config = InlayHintsConfig {
+            type_hints: true,
+            parameter_hints: true,
+            chaining_hints: true,
+            render_templates: HintRenderTemplates {
+                type_hints: template(": ", ""),
+                parameter_hints: template("", ":"),
+                chaining_hints: template("→ ", ""),
+                ..HintRenderTemplates::EMPTY
+            },
+            ..DISABLED_CONFIG
+        };
+        let labels = |config: &InlayHintsConfig| {
+            analysis
+                .inlay_hints(file_id, config)
+                .unwrap()
+                .iter()
+                .map(|it| (it.kind, it.label.to_string()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            labels(&config),
+            vec![
+                (InlayKind::TypeHint, ": A".to_string()),
+                (InlayKind::ChainingHint, "→ A".to_string()),
+                (InlayKind::ChainingHint, "→ B".to_string()),
+                (InlayKind::ParameterHint, "count:".to_string()),
+                (InlayKind::ParameterHint, "flag:".to_string()),
+            ]
+        );
+
+        let config = InlayHintsConfig { render_templates: HintRenderTemplates::EMPTY, ..config };
+        assert_eq!(
+            labels(&config),
+            vec![
+                (InlayKind::TypeHint, "A".to_string()),
+                (InlayKind::ChainingHint, "A".to_string()),
+                (InlayKind::ChainingHint, "B".to_string()),
+                (InlayKind::ParameterHint, "count".to_string()),
+                (InlayKind::ParameterHint, "flag".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn deref_coercion_adjustment_hints() {
+        check_with_config(
+            InlayHintsConfig { adjustment_hints: true, ..DISABLED_CONFIG },
+            r#"
+#[lang = "deref"]
+trait Deref {
+    type Target: ?Sized;
+    fn deref(&self) -> &Self::Target;
+}
+
+struct String;
+
+impl Deref for String {
+    type Target = str;
+    fn deref(&self) -> &str { loop {} }
+}
+
+fn takes_str(s: &str) {}
+fn takes_mut_str(s: &mut str) {}
+
+fn main() {
+    let string_var = String;
+    takes_str(&string_var);
+            //^^^^^^^^^^^ as &str
+    takes_str("literal");
+    takes_mut_str(&string_var);
+    let str_var: &str = "literal";
+    takes_str(str_var);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn cached_hints_survive_unrelated_changes() {
+        // Each crate gets its own source root, like the packages of a workspace.
+        let (main, dep, other) = (FileId(0), FileId(1), FileId(2));
+        let mut change = Change::new();
+        let mut crate_graph = CrateGraph::default();
+        let mut roots = Vec::new();
+        let mut add_crate = |file_id: FileId, text: &str| {
+            let mut file_set = FileSet::default();
+            file_set.insert(file_id, VfsPath::new_virtual_path(format!("/{}/lib.rs", file_id.0)));
+            roots.push(SourceRoot::new_local(file_set));
+            change.change_file(file_id, Some(Arc::new(text.to_string())));
+            crate_graph.add_crate_root(
+                file_id,
+                Edition::Edition2018,
+                None,
+                CfgOptions::default(),
+                Env::default(),
+                Default::default(),
+            )
+        };
+        let main_crate = add_crate(main, "fn main() {\n    let value = dep::make();\n}\n");
+        let dep_crate = add_crate(dep, "pub fn make() -> u32 { 0 }");
+        add_crate(other, "pub fn other() {}");
+        crate_graph.add_dep(main_crate, CrateName::new("dep").unwrap(), dep_crate).unwrap();
+        change.set_roots(roots);
+        change.set_crate_graph(crate_graph);
+        let mut host = AnalysisHost::default();
+        host.apply_change(change);
+
+        let labels = |host: &AnalysisHost, config: &InlayHintsConfig| {
+            let hints = host.analysis().inlay_hints(main, config).unwrap();
+            hints.into_iter().map(|it| it.label.to_string()).collect::<Vec<_>>()
+        };
+        let config = InlayHintsConfig { type_hints: true, ..DISABLED_CONFIG };
+        let item = {
+            let file = host.analysis().parse(main).unwrap();
+            SyntaxNodePtr::new(&file.syntax().first_child().unwrap())
+        };
+        let cached = |host: &AnalysisHost| {
+            host.raw_database().item_inlay_hints(main, item.clone(), config.clone())
+        };
+
+        assert_eq!(labels(&host, &config), vec!["u32"]);
+        let hints = cached(&host);
+
+        let with_ids = InlayHintsConfig { hint_ids: true, ..config.clone() };
+        assert_eq!(labels(&host, &with_ids), vec!["u32"]);
+        assert!(Arc::ptr_eq(&hints, &cached(&host)));
+
+        let mut change = Change::new();
+        change.change_file(other, Some(Arc::new("pub fn other() -> u8 { 0 }".to_string())));
+        host.apply_change(change);
+        assert!(Arc::ptr_eq(&hints, &cached(&host)));
+
+        let mut change = Change::new();
+        change.change_file(dep, Some(Arc::new("pub fn make() -> i64 { 0 }".to_string())));
+        host.apply_change(change);
+        assert!(!Arc::ptr_eq(&hints, &cached(&host)));
+        assert_eq!(labels(&host, &config), vec!["i64"]);
+    }
+
+    #[test]
+    fn hints_without_analysis_host() {
+        let (db, file_id) = RootDatabase::with_single_file("fn main() { let value = 92u8; }");
+        let config = InlayHintsConfig { type_hints: true, ..DISABLED_CONFIG };
+        let hints = ide_db::inlay_hints::inlay_hints(&db, file_id, &config);
+        let labels = hints.into_iter().map(|it| it.label.to_string()).collect::<Vec<_>>();
+        assert_eq!(labels, vec!["u8"]);
+    }
+
+    #[test]
+    fn lazy_deref_coercion_adjustment_hints() {
+        check_lazy_labels(
+            InlayHintsConfig { adjustment_hints: true, ..DISABLED_CONFIG },
+            r#"
+#[lang = "deref"]
+trait Deref {
+    type Target: ?Sized;
+    fn deref(&self) -> &Self::Target;
+}
+
+struct String;
+
+impl Deref for String {
+    type Target = str;
+    fn deref(&self) -> &str { loop {} }
+}
+
+fn takes_str(s: &str) {}
+
+fn main() {
+    let string_var = String;
+    takes_str(&string_var);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn hints_for_already_parsed_file() {
+        let (analysis, file_id) = fixture::file(
+            r#"
+struct A(B);
+impl A { fn into_b(self) -> B { self.0 } }
+struct B;
+impl B { fn into_a(self) -> A { A(self) } }
+fn make(count: i32) -> A { A(B) }
+fn main() {
+    let a = make(1)
+        .into_b()
+        .into_a();
+}
+"#,
+        );
+        let config = InlayHintsConfig {
+            type_hints: true,
+            parameter_hints: true,
+            chaining_hints: true,
+            hint_ids: true,
+            ..DISABLED_CONFIG
+        };
+        let expected = analysis.inlay_hints(file_id, &config).unwrap();
+        let file = analysis.parse(file_id).unwrap();
+        let actual = inlay_hints_in_file(&analysis.db, file_id, &file, None, &config);
+        assert_eq!(format!("{:?}", actual), format!("{:?}", expected));
+    }
+
+    #[test]
+    fn hide_hints_when_type_in_rhs() {
+        check_with_config(
+            InlayHintsConfig {
+                type_hints: true,
+                hide_hints_when_type_in_rhs: true,
+                ..DISABLED_CONFIG
+            },
+            r#"
+enum MyEnum { Variant }
+struct Foo;
+impl Foo {
+    const ZERO: Foo = Foo;
+    const COUNT: u32 = 0;
+}
+const LIMIT: Foo = Foo;
+
+fn main() {
+    let x = MyEnum::Variant;
+    let zero = Foo::ZERO;
+    let count = Foo::COUNT;
+      //^^^^^ u32
+    let limit = LIMIT;
+      //^^^^^ Foo
+    let multi =
+      //^^^^^ MyEnum
+        MyEnum::Variant;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inlay_hints_count_matches_hints() {
+        let ra_fixture = format!(
+            "//- /main.rs crate:main deps:core\n{}\n{}",
+            r#"
+use core::iter;
+
+struct Pair(u8, u8);
+struct A(B);
+impl A { fn into_b(self) -> B { self.0 } fn by_ref(&self) -> &A { self } }
+struct B;
+impl B { fn into_a(self) -> A { A(self) } }
+
+fn takes(x: u8, y: u8) {}
+
+fn main() {
+    let repeated = iter::repeat(0).take(5);
+    let (x, y) = (1, 2);
+    takes(y, x);
+    let pair = Pair(x, y);
+    let float = 1.0;
+    let a = A(B)
+        .into_b()
+        .into_a()
+        .by_ref()
+        .by_ref()
+        .by_ref();
+}
+"#,
+            FamousDefs::FIXTURE
+        );
+        let (analysis, file_id) = fixture::file(&ra_fixture);
+        let everything = InlayHintsConfig {
+            self_param_hints: true,
+            tuple_constructor_hints: true,
+            question_mark_chain_hints: true,
+            surprise_inference_hints: true,
+            adjustment_hints: true,
+            arg_order_mismatch_hints: true,
+            ..InlayHintsConfig::default()
+        };
+        let configs = vec![
+            InlayHintsConfig::default(),
+            everything.clone(),
+            InlayHintsConfig { dedup_adjacent_chaining_hints: true, ..everything.clone() },
+            InlayHintsConfig { combine_colocated_hints: true, ..everything.clone() },
+            InlayHintsConfig { lazy_labels: true, ..everything },
+            DISABLED_CONFIG,
+        ];
+        for config in configs {
+            let hints = analysis.inlay_hints(file_id, &config).unwrap();
+            assert_eq!(analysis.inlay_hints_count(file_id, &config).unwrap(), hints.len());
+        }
+    }
+
+    #[test]
+    fn inlay_hints_count_keeps_colocated_hints() {
+        let (analysis, file_id) = fixture::file(
+            r#"
+struct Token;
+fn consume(token: Token) {}
+
+fn main() {
+    let (read, mut write, moved) = (Token, Token, Token);
+    let f = || {
+        let _ = &read;
+        write = Token;
+        consume(moved);
+    };
+}
+"#,
+        );
+        let config = InlayHintsConfig { closure_capture_hints: true, ..DISABLED_CONFIG };
+        for config in &[config.clone(), InlayHintsConfig { lazy_labels: true, ..config }] {
+            let hints = analysis.inlay_hints(file_id, config).unwrap();
+            assert_eq!(hints.len(), 3);
+            assert_eq!(analysis.inlay_hints_count(file_id, config).unwrap(), 3);
+        }
+    }
+
+    #[test]
+    fn parse_through_question_mark_hints() {
+        check_with_config(
+            InlayHintsConfig { type_hints: true, ..DISABLED_CONFIG },
+            r#"
+use core::ops::Try;
+
+enum Result<T, E> { Ok(T), Err(E) }
+impl<T, E> Try for Result<T, E> {
+    type Ok = T;
+    type Error = E;
+}
+
+trait FromStr { type Err; }
+struct ParseIntError;
+impl FromStr for i32 { type Err = ParseIntError; }
+impl FromStr for u64 { type Err = ParseIntError; }
+
+struct Text;
+impl Text {
+    fn parse<F: FromStr>(&self) -> Result<F, F::Err> { loop {} }
+}
+
+fn annotated(s: Text) -> Result<(), ParseIntError> {
+    let n: i32 = s.parse()?;
+    let m = n;
+      //^ i32
+    Result::Ok(())
+}
+
+fn returned(s: Text) -> Result<u64, ParseIntError> {
+    let n = s.parse()?;
+      //^ u64
+    Result::Ok(n)
+}
+
+fn turbofish(s: Text) -> Result<(), ParseIntError> {
+    let n = s.parse::<i32>()?;
+      //^ i32
+    Result::Ok(())
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn arg_list_type_hints() {
+        check_with_config(
+            InlayHintsConfig { arg_list_type_hints: true, ..DISABLED_CONFIG },
+            r#"
+struct Counter;
+impl Counter {
+    fn add(&mut self, by: u8) {}
+}
+
+fn mixed(a: i32, b: &str, c: bool) {}
+fn unit() {}
+
+fn main() {
+    mixed(1, "two", true);
+       //^^^^^^^^^^^^^^^^ (i32, &str, bool)
+    let mut counter = Counter;
+    counter.add(3);
+             //^^^ (u8,)
+    unit();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn expand_impl_trait_hints() {
+        check_with_config(
+            InlayHintsConfig { type_hints: true, expand_impl_trait_hints: true, ..DISABLED_CONFIG },
+            r#"
+use core::future::{ready, Future};
+
+struct Wrap<F>(F);
+impl<F> Future for Wrap<F> { type Output = i32; }
+
+fn make() -> impl Future<Output = i32> { ready(0) }
+fn generic<T>(value: T) -> impl Future<Output = T> { ready(value) }
+fn recursive() -> impl Future<Output = i32> { recursive() }
+fn closure() -> impl Future<Output = i32> { Wrap(|| 0) }
+async fn run() -> i32 { 0 }
+
+fn main() {
+    let made = make();
+      //^^^^ Ready<i32>
+    let generic = generic(0);
+      //^^^^^^^ impl Future<Output = i32>
+    let recursive = recursive();
+      //^^^^^^^^^ impl Future<Output = i32>
+    let closure = closure();
+      //^^^^^^^ impl Future<Output = i32>
+    let run = run();
+      //^^^ impl Future<Output = i32>
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn while_let_iterator_next() {
+        check_with_config(
+            InlayHintsConfig { type_hints: true, ..DISABLED_CONFIG },
+            r#"
+struct Counter;
+impl Iterator for Counter {
+    type Item = u32;
+    fn next(&mut self) -> Option<u32> { None }
+}
+
+fn main() {
+    let mut counter = Counter.take(3);
+      //^^^^^^^^^^^ impl Iterator<Item = u32>
+    while let Some(x) = counter.next() {}
+                 //^ u32
+    let mut pairs = Counter.enumerate();
+      //^^^^^^^^^ impl Iterator<Item = (usize, u32)>
+    while let Some((_, x)) = pairs.next() {}
+                     //^ u32
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn minimal_type_hints() {
+        check_with_config(
+            InlayHintsConfig { type_hints: true, minimal_type_hints: true, ..DISABLED_CONFIG },
+            r#"
+struct Vec<T> { buf: T }
+struct HashMap<K, V> { key: K, value: V }
+struct Key;
+struct Value;
+
+fn nested() -> Vec<HashMap<Key, Value>> { loop {} }
+
+fn main() {
+    let maps = nested();
+      //^^^^ Vec<_>
+    let map = &HashMap { key: 0u8, value: Vec { buf: Key } };
+      //^^^ &HashMap<_, _>
+    let pair = (Key, Vec { buf: 0u32 });
+      //^^^^ (Key, Vec<_>)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn index_output_hints_nested() {
+        check_with_config(
+            InlayHintsConfig { type_hints: true, index_output_hints: true, ..DISABLED_CONFIG },
+            r#"
+use core::ops::Index;
+
+struct Matrix { rows: [[f32; 3]; 3] }
+impl Index<usize> for Matrix {
+    type Output = [f32; 3];
+    fn index(&self, row: usize) -> &[f32; 3] {
+        &self.rows[row]
+       //^^^^^^^^^^^^^^ [f32; _]
+    }
+}
+
+fn main(matrix: &Matrix, columns: &[Vec<u8>], i: usize, j: usize) {
+    let y =
+      //^ f32
+        matrix[i][j];
+      //^^^^^^^^^ [f32; _]
+    let item =
+      //^^^^ u8
+        columns[j].item;
+      //^^^^^^^^^^ Vec<u8>
+}
+
+struct Vec<T> { item: T }
+"#,
+        );
+    }
+
+    #[test]
+    fn ref_binding_hints() {
+        check_with_kinds(
+            InlayHintsConfig {
+                type_hints: true,
+                adjustment_hints: true,
+                surprise_inference_hints: true,
+                ..DISABLED_CONFIG
+            },
+            r#"
+struct Item;
+
+fn main(mut opt: Option<Item>) {
+    if let Some(ref x) = opt {}
+              //^^^^^ type: &Item
+    if let Some(ref mut x) = opt {}
+              //^^^^^^^^^ type: &mut Item
+    if let Some(x) = &opt {}
+              //^ type: &Item
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn operator_hints() {
+        check_with_config(
+            InlayHintsConfig { type_hints: true, operator_hints: true, ..DISABLED_CONFIG },
+            r#"
+use core::ops::{Add, Mul};
+
+struct Scalar;
+struct Vector;
+struct Matrix;
+impl Mul for Vector {
+    type Output = Scalar;
+    fn mul(self, rhs: Vector) -> Scalar { Scalar }
+}
+impl Mul<Vector> for Matrix {
+    type Output = Vector;
+    fn mul(self, rhs: Vector) -> Vector { rhs }
+}
+impl Add for Matrix {
+    type Output = Matrix;
+    fn add(self, rhs: Matrix) -> Matrix { rhs }
+}
+
+fn take<T>(value: T) {}
+
+fn main(a: Vector, b: Vector, m: Matrix, n: Matrix, x: i32, y: i32) {
+    take(a * b);
+       //^^^^^ Scalar
+    take(m + n);
+    take(x + y);
+    let v = m * a;
+          //^^^^^ Vector
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn hints_follow_crate_edition() {
+        // 2015 resolves import paths from the crate root, 2018 from the
+        // extern prelude.
+        let (analysis, file_ids) = fixture::files(
+            r#"
+//- /old.rs crate:old deps:shapes edition:2015
+mod shapes {
+    pub struct Local;
+    pub fn make() -> Local { Local }
+}
+mod m {
+    use shapes::make;
+    fn f() {
+        let shape = make();
+          //^^^^^ Local
+    }
+}
+
+//- /new.rs crate:new deps:shapes edition:2018
+mod shapes {
+    pub struct Local;
+    pub fn make() -> Local { Local }
+}
+mod m {
+    use shapes::make;
+    fn f() {
+        let shape = make();
+          //^^^^^ Extern
+    }
+}
+
+//- /lib.rs crate:shapes
+pub struct Extern;
+pub fn make() -> Extern { Extern }
+"#,
+        );
+        let config = InlayHintsConfig { type_hints: true, ..DISABLED_CONFIG };
+        for file_id in file_ids {
+            let expected = extract_annotations(&analysis.file_text(file_id).unwrap());
+            let actual = analysis
+                .inlay_hints(file_id, &config)
+                .unwrap()
+                .into_iter()
+                .map(|it| (it.range, it.label.to_string()))
+                .collect::<Vec<_>>();
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn deref_hints() {
+        check_with_config(
+            InlayHintsConfig { type_hints: true, deref_hints: true, ..DISABLED_CONFIG },
+            r#"
+use core::ops::Deref;
+
+struct Meters(f64);
+struct Wrapper { meters: Meters }
+impl Deref for Wrapper {
+    type Target = Meters;
+    fn deref(&self) -> &Meters { &self.meters }
+}
+
+fn take<T>(value: T) {}
+
+fn main(wrapper: Wrapper, count: &mut u32) {
+    take(&*wrapper);
+        //^^^^^^^^ Meters
+    *count += 1;
+  //^^^^^^ u32
+    let meters =
+      //^^^^^^ &Meters
+        &*wrapper;
+       //^^^^^^^^ Meters
+    let inner = *count;
+      //^^^^^ u32
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn normalize_projections() {
+        check_with_config(
+            InlayHintsConfig { type_hints: true, normalize_projections: true, ..DISABLED_CONFIG },
+            r#"
+trait Shape { type Area; }
+struct Square;
+impl Shape for Square { type Area = u32; }
+struct Circle;
+
+fn areas<S: Shape>(shape: S) -> impl Iterator<Item = S::Area> { loop {} }
+fn nested<S: Shape>(shape: S) -> Option<impl Iterator<Item = Option<S::Area>>> { loop {} }
+fn unknown<S>(shape: S) -> impl Iterator<Item = <S as Shape>::Area> { loop {} }
+
+fn main() {
+    let squares = areas(Square);
+      //^^^^^^^ impl Iterator<Item = u32>
+    let nested = nested(Square);
+      //^^^^^^ Option<impl Iterator<Item = Option<u32>>>
+    let circles = unknown(Circle);
+      //^^^^^^^ impl Iterator<Item = <Circle as Shape>::Area>
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn dyn_trait_self_returns() {
+        check_with_config(
+            InlayHintsConfig { type_hints: true, ..DISABLED_CONFIG },
+            r#"
+struct Box<T: ?Sized>(T);
+impl<T: ?Sized> core::ops::Deref for Box<T> { type Target = T; }
+trait Shape {
+    fn boxed(&self) -> Box<Self>;
+    fn pair(&self) -> (Box<Self>, u32);
+}
+trait Convert<T> {
+    fn convert(&self, t: T) -> Box<Self>;
+}
+
+fn f(shape: &dyn Shape, owned: Box<dyn Shape>, conv: &dyn Convert<u32>) {
+    let boxed = shape.boxed();
+      //^^^^^ Box<dyn Shape>
+    let pair = shape.pair();
+      //^^^^ (Box<dyn Shape>, u32)
+    let boxed = owned.boxed();
+      //^^^^^ Box<dyn Shape>
+    let converted = conv.convert(1);
+      //^^^^^^^^^ Box<dyn Convert<u32>>
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn compact_hints_round_trip() {
+        let ra_fixture = format!(
+            "//- /main.rs crate:main deps:core\n{}\n{}",
+            r#"
+struct Wrapper<T>(T);
+impl<T> Wrapper<T> { fn get(self) -> T { self.0 } fn wrap(self) -> Wrapper<Self> { Wrapper(self) } }
+fn add(left: u32, right: u32) -> u32 { left + right }
+
+fn main() {
+    let a = add(1, 2);
+    let b = add(a, 3);
+    let wrapped = Wrapper(Wrapper(a))
+        .wrap()
+        .get()
+        .get();
+    let long_long_name = Wrapper("text");
+}
+"#,
+            FamousDefs::FIXTURE
+        );
+        let (analysis, file_id) = fixture::file(&ra_fixture);
+        let config = InlayHintsConfig {
+            type_hints: true,
+            parameter_hints: true,
+            chaining_hints: true,
+            ..DISABLED_CONFIG
+        };
+        let hints = analysis.inlay_hints(file_id, &config).unwrap();
+        let compact = analysis.inlay_hints_compact(file_id, &config).unwrap();
+        // Labels repeated by several hints are stored once.
+        assert!(compact.labels.len() < hints.len());
+        let decoded = compact.decode().unwrap();
+        let summary = |hints: &[InlayHint]| {
+            hints
+                .iter()
+                .map(|it| (it.range, it.kind, it.label.to_string(), it.pad_left, it.pad_right))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(summary(&hints), summary(&decoded));
+
+        let mut truncated = compact.clone();
+        truncated.data.pop();
+        assert_eq!(truncated.decode().map(|it| it.len()), None);
+    }
+
+    #[test]
+    fn multi_dyn_trait_bounds_collapsed() {
+        check_with_config(
+            InlayHintsConfig {
+                type_hints: true,
+                max_length: Some(34),
+                collapse_dyn_bounds: true,
+                ..DISABLED_CONFIG
+            },
+            r#"
+pub struct Vec<T> {}
+
+impl<T> Vec<T> {
+    pub fn new() -> Self { Vec {} }
+}
+
+pub struct Box<T> {}
+pub struct Pin<P> { pointer: P }
+
+fn vec<T>() -> Vec<T> { Vec::new() }
+
+trait Display {}
+trait Sync {}
+trait Send {}
+
+fn main() {
+    let _v = vec::<Box<&(dyn Display + Sync + Send)>>();
+      //^^ Vec<Box<&(dyn Display + …)>>
+    let _v = vec::<Box<*const (dyn Display + Sync)>>();
+      //^^ Vec<Box<*const (dyn Display + …)>>
+    let _v = vec::<Box<dyn Display + Sync>>();
+      //^^ Vec<Box<dyn Display + Sync>>
+    let _v = vec::<Box<dyn Display + Sync + Send>>();
+      //^^ Vec<Box<dyn Display + …>>
+}
+"#,
+        );
+        check_with_config(
+            InlayHintsConfig {
+                type_hints: true,
+                max_length: Some(42),
+                collapse_dyn_bounds: true,
+                ..DISABLED_CONFIG
+            },
+            r#"
+pub struct Vec<T> {}
+pub struct Box<T> {}
+pub struct Pin<P> { pointer: P }
+
+fn vec<T>() -> Vec<T> { loop {} }
+
+trait Send {}
+use core::future::Future;
+
+fn main() {
+    let _v = vec::<Pin<Box<dyn Future<Output = u8> + Send>>>();
+      //^^ Vec<Pin<Box<dyn Future<Output = u8> + …>>>
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn fold_accumulator_hints() {
+        check_with_config(
+            InlayHintsConfig { type_hints: true, ..DISABLED_CONFIG },
+            r#"
+#[lang = "fn_once"]
+trait FnOnce<Args> { type Output; }
+#[lang = "fn_mut"]
+trait FnMut<Args>: FnOnce<Args> {}
+
+trait Fold: Iterator + Sized {
+    fn fold<B, F: FnMut(B, Self::Item) -> B>(self, init: B, f: F) -> B { loop {} }
+    fn try_fold<B, F>(&mut self, init: B, f: F) -> Option<B>
+    where
+        F: FnMut(B, Self::Item) -> Option<B>,
+    {
+        loop {}
+    }
+}
+impl<I: Iterator> Fold for I {}
+
+struct Vec<T>(T);
+impl<T> Vec<T> { fn iter(&self) -> Iter<'_, T> { loop {} } }
+struct Iter<'a, T>(&'a T);
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<&'a T> { loop {} }
+}
+
+fn main(v: Vec<i32>) {
+    let sum = v.iter().fold(0, |acc,
+      //^^^ i32                 ^^^ i32
+                                x| acc + x);
+                              //^ &i32
+    let product = v.iter().try_fold(1u64, |acc,
+      //^^^^^^^ Option<u64>                ^^^ u64
+                                           x| Some(acc));
+                                         //^ &i32
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn chaining_hints_anchor() {
+        let (analysis, file_id) = fixture::file(
+            r#"
+struct A(B);
+impl A { fn into_b(self) -> B { self.0 } }
+struct B(C);
+impl B { fn into_c(self) -> C { self.0 } }
+struct C;
+
+fn main() {
+    let c = A(B(C))
+        .into_b() // This is a comment
+        .into_c();
+}
+"#,
+        );
+        let text = analysis.file_text(file_id).unwrap();
+        let dot =
+            |method: &str| TextSize::from(text.find(&format!(".{}(", method)).unwrap() as u32);
+        let anchors = |anchor| {
+            let config = InlayHintsConfig {
+                chaining_hints: true,
+                chaining_hints_anchor: anchor,
+                ..DISABLED_CONFIG
+            };
+            let hints = analysis.inlay_hints(file_id, &config).unwrap();
+            hints.into_iter().map(|it| (it.range, it.label.to_string())).collect::<Vec<_>>()
+        };
+
+        let receiver = anchors(ChainingHintsAnchor::Receiver);
+        let link_start = TextSize::from(text.find("A(B(C))").unwrap() as u32);
+        let link_end = |method: &str| dot(method) + TextSize::of(method) + TextSize::of(".()");
+        assert_eq!(
+            receiver,
+            vec![
+                (TextRange::at(link_start, TextSize::of("A(B(C))")), "A".to_string()),
+                (TextRange::new(link_start, link_end("into_b")), "B".to_string()),
+            ]
+        );
+
+        let after_dot = anchors(ChainingHintsAnchor::AfterDot);
+        assert_eq!(
+            after_dot,
+            vec![
+                (TextRange::at(dot("into_b"), TextSize::of(".")), "A".to_string()),
+                (TextRange::at(dot("into_c"), TextSize::of(".")), "B".to_string()),
+            ]
+        );
+
+        // Hints anchored on a `.` can still be looked up and resolved there.
+        let config = InlayHintsConfig {
+            chaining_hints: true,
+            chaining_hints_anchor: ChainingHintsAnchor::AfterDot,
+            ..DISABLED_CONFIG
+        };
+        for (range, label) in after_dot {
+            let position = FilePosition { file_id, offset: range.start() };
+            let hint = analysis.inlay_hint_at(position, &config).unwrap().unwrap();
+            assert_eq!((hint.range, hint.label.to_string()), (range, label.clone()));
+            let resolved = analysis
+                .resolve_inlay_hint(file_id, range, InlayKind::ChainingHint, &config)
+                .unwrap()
+                .unwrap();
+            assert_eq!((resolved.range, resolved.label.to_string()), (range, label));
+        }
+    }
+
+    #[test]
+    fn literal_type_hints() {
+        check_with_config(
+            InlayHintsConfig { literal_type_hints: true, ..DISABLED_CONFIG },
+            r#"
+fn takes_u8(n: u8) {}
+fn takes_f32(x: f32) {}
+fn takes_i32(n: i32) {}
+
+fn main() {
+    takes_u8(5);
+           //^ u8
+    takes_f32(1.5);
+            //^^^ f32
+    takes_u8(0x_ff);
+           //^^^^^ u8
+    takes_i32(5);
+    takes_u8(5u8);
+    let sum: u64 = 1
+                 //^ u64
+        + 2;
+        //^ u64
+    let negative: i8 = -1;
+                      //^ i8
+    let default = 5;
+    let float = 2.0;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn chaining_hints_across_question_marks() {
+        let ra_fixture = format!(
+            "//- /main.rs crate:main deps:core\n{}\n{}",
+            r#"
+use core::ops::Try;
+
+enum Result<T, E> { Ok(T), Err(E) }
+impl<T, E> Try for Result<T, E> {
+    type Ok = T;
+    type Error = E;
+}
+
+struct Error;
+struct Conn { id: u32 }
+struct Query;
+struct Rows;
+impl Conn { fn query(&self) -> Result<Query, Error> { loop {} } }
+impl Query { fn rows(self) -> Result<Rows, Error> { loop {} } }
+impl Rows { fn count(self) -> usize { 0 } }
+
+fn run(conn: Conn) -> Result<usize, Error> {
+    let n = conn
+        .query()?
+        .rows()? // comment
+        .count();
+    let m = conn.query()?.rows()?
+        .count();
+    Result::Ok(n + m)
+}
+"#,
+            FamousDefs::FIXTURE
+        );
+        let (analysis, file_id) = fixture::file(&ra_fixture);
+        let text = analysis.file_text(file_id).unwrap();
+        let config = InlayHintsConfig { chaining_hints: true, ..DISABLED_CONFIG };
+        let hints = analysis.inlay_hints(file_id, &config).unwrap();
+        let actual =
+            hints.iter().map(|it| (&text[it.range], it.label.to_string())).collect::<Vec<_>>();
+        // The links ending with `?` are hinted with the type they unwrap to,
+        // and the calls before the `?`s get no hint of their own.
+        let expected = vec![
+            ("conn", "Conn".to_string()),
+            ("conn\n        .query()?", "Query".to_string()),
+            ("conn\n        .query()?\n        .rows()?", "Rows".to_string()),
+            ("conn.query()?.rows()?", "Rows".to_string()),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn inferred_constructor_type_hints() {
+        check_with_config(
+            InlayHintsConfig { inferred_constructor_type_hints: true, ..DISABLED_CONFIG },
+            r#"
+enum Either<L, R> { Left(L), Right(R) }
+enum Shape { Circle(u32) }
+
+fn take<T>(value: T) {}
+fn take_opt(value: Option<u8>) {}
+
+fn main() {
+    take(Some(5));
+       //^^^^^^^ Option<i32>
+    take_opt(None);
+           //^^^^ Option<u8>
+    take(Either::Left::<_, bool>(1));
+    take(Either::<_, bool>::Left(1u16));
+    take(Either::Right(Some(true)));
+                     //^^^^^^^^^^ Option<bool>
+    take(Either::Right::<u8, _>(
+        Some(true),
+      //^^^^^^^^^^ Option<bool>
+    ));
+    take(Shape::Circle(1));
+    let bound = Some(5);
+    let nested = (Some(5), 1);
+                //^^^^^^^ Option<i32>
+}
+"#,
+        );
+    }
 }
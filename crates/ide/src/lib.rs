@@ -18,7 +18,6 @@ macro_rules! eprintln {
 #[cfg(test)]
 mod fixture;
 
-mod markup;
 mod prime_caches;
 mod display;
 
@@ -75,8 +74,6 @@ pub use crate::{
     file_structure::StructureNode,
     folding_ranges::{Fold, FoldKind},
     hover::{HoverAction, HoverConfig, HoverGotoTypeData, HoverResult},
-    inlay_hints::{InlayHint, InlayHintsConfig, InlayKind},
-    markup::Markup,
     references::{Declaration, Reference, ReferenceAccess, ReferenceKind, ReferenceSearchResult},
     runnables::{Runnable, RunnableKind, TestId},
     syntax_highlighting::{
@@ -93,8 +90,13 @@ pub use base_db::{
 };
 pub use hir::{Documentation, Semantics};
 pub use ide_db::{
+    inlay_hints::{
+        ChainingHintsAnchor, CompactHints, HintRenderTemplates, HintTemplate, InlayHint,
+        InlayHintLabel, InlayHintLabelPart, InlayHintsConfig, InlayKind, TruncationPolicy,
+    },
     label::Label,
     line_index::{LineCol, LineIndex},
+    markup::Markup,
     search::SearchScope,
     source_change::{FileSystemEdit, SourceChange, SourceFileEdit},
     symbol_index::Query,
@@ -308,7 +310,11 @@ impl Analysis {
         self.with_db(|db| file_structure::file_structure(&db.parse(file_id).tree()))
     }
 
-    /// Returns a list of the places in the file where type hints can be displayed.
+    /// Returns a list of the places in the file where type hints can be displayed,
+    /// sorted by range and kind.
+    ///
+    /// The hints are cached until a change to the file, or to a crate it
+    /// depends on.
     pub fn inlay_hints(
         &self,
         file_id: FileId,
@@ -317,6 +323,72 @@ impl Analysis {
         self.with_db(|db| inlay_hints::inlay_hints(db, file_id, config))
     }
 
+    /// Returns the inlay hints for the part of the file intersecting `range`,
+    /// or for the whole file if `range` is `None`. Only the hints of whole
+    /// files are cached.
+    pub fn inlay_hints_range(
+        &self,
+        file_id: FileId,
+        range: Option<TextRange>,
+        config: &InlayHintsConfig,
+    ) -> Cancelable<Vec<InlayHint>> {
+        self.with_db(|db| inlay_hints::inlay_hints_range(db, file_id, range, config))
+    }
+
+    /// Returns the number of inlay hints in the file, without rendering their
+    /// labels where possible.
+    pub fn inlay_hints_count(
+        &self,
+        file_id: FileId,
+        config: &InlayHintsConfig,
+    ) -> Cancelable<usize> {
+        self.with_db(|db| inlay_hints::inlay_hints_count(db, file_id, config))
+    }
+
+    /// Returns the inlay hints of the file in the compact encoding of
+    /// [`CompactHints`].
+    pub fn inlay_hints_compact(
+        &self,
+        file_id: FileId,
+        config: &InlayHintsConfig,
+    ) -> Cancelable<CompactHints> {
+        self.with_db(|db| inlay_hints::inlay_hints_compact(db, file_id, config))
+    }
+
+    /// Computes the full inlay hint for one returned with
+    /// `InlayHintsConfig::lazy_labels` set.
+    pub fn resolve_inlay_hint(
+        &self,
+        file_id: FileId,
+        range: TextRange,
+        kind: InlayKind,
+        config: &InlayHintsConfig,
+    ) -> Cancelable<Option<InlayHint>> {
+        self.with_db(|db| inlay_hints::resolve_inlay_hint(db, file_id, range, kind, config))
+    }
+
+    /// Returns the innermost inlay hint covering `position`, without
+    /// computing the hints for the whole file.
+    pub fn inlay_hint_at(
+        &self,
+        position: FilePosition,
+        config: &InlayHintsConfig,
+    ) -> Cancelable<Option<InlayHint>> {
+        self.with_db(|db| inlay_hints::inlay_hint_at(db, position.file_id, position.offset, config))
+    }
+
+    /// Returns a type hint for the innermost expression covering `position`,
+    /// even one that wouldn't get a hint otherwise.
+    pub fn inlay_hint_for_expr_at(
+        &self,
+        position: FilePosition,
+        config: &InlayHintsConfig,
+    ) -> Cancelable<Option<InlayHint>> {
+        self.with_db(|db| {
+            inlay_hints::inlay_hint_for_expr_at(db, position.file_id, position.offset, config)
+        })
+    }
+
     /// Returns the set of folding ranges.
     pub fn folding_ranges(&self, file_id: FileId) -> Cancelable<Vec<Fold>> {
         self.with_db(|db| folding_ranges::folding_ranges(&db.parse(file_id).tree()))
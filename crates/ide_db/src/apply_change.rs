@@ -90,6 +90,8 @@ impl RootDatabase {
         hir::db::ExprScopesQuery.in_db(self).sweep(sweep);
         hir::db::InferQueryQuery.in_db(self).sweep(sweep);
         hir::db::BodyQuery.in_db(self).sweep(sweep);
+
+        crate::inlay_hints::ItemInlayHintsQuery.in_db(self).sweep(sweep);
     }
 
     // Feature: Memory Usage
@@ -205,6 +207,10 @@ impl RootDatabase {
 
             // LineIndexDatabase
             crate::LineIndexQuery
+
+            // InlayHintsDatabase
+            crate::inlay_hints::ItemInlayHintsQuery
+            crate::inlay_hints::AssocShorteningDefsQuery
         ];
 
         // To collect interned data, we need to bump the revision counter by performing a synthetic
@@ -0,0 +1,298 @@
+//! A module with ide helpers for high-level ide features.
+use hir::{Crate, Enum, Module, ScopeDef, Semantics, Struct, Trait};
+use syntax::ast;
+
+use crate::RootDatabase;
+
+/// Helps with finding well-know things inside the standard library. This is
+/// somewhat similar to the known paths infra inside hir, but it different; We
+/// want to make sure that IDE specific paths don't become interesting inside
+/// the compiler itself as well.
+pub struct FamousDefs<'a, 'b>(pub &'a Semantics<'b, RootDatabase>, pub Crate);
+
+#[allow(non_snake_case)]
+impl FamousDefs<'_, '_> {
+    pub const FIXTURE: &'static str = r#"//- /libcore.rs crate:core
+pub mod convert {
+    pub trait From<T> {
+        fn from(t: T) -> Self;
+    }
+}
+
+pub mod iter {
+    pub use self::traits::{collect::IntoIterator, iterator::Iterator};
+    mod traits {
+        pub(crate) mod iterator {
+            use crate::option::Option;
+            pub trait Iterator {
+                type Item;
+                fn next(&mut self) -> Option<Self::Item>;
+                fn by_ref(&mut self) -> &mut Self {
+                    self
+                }
+                fn take(self, n: usize) -> crate::iter::Take<Self> {
+                    crate::iter::Take { inner: self }
+                }
+                fn enumerate(self) -> crate::iter::Enumerate<Self> {
+                    crate::iter::Enumerate { inner: self }
+                }
+            }
+
+            impl<I: Iterator> Iterator for &mut I {
+                type Item = I::Item;
+                fn next(&mut self) -> Option<I::Item> {
+                    (**self).next()
+                }
+            }
+        }
+        pub(crate) mod collect {
+            use super::iterator::Iterator;
+            pub trait IntoIterator {
+                type Item;
+            }
+
+            impl<I: Iterator> IntoIterator for I {
+                type Item = I::Item;
+            }
+        }
+    }
+
+    pub use self::sources::*;
+    pub(crate) mod sources {
+        use super::Iterator;
+        use crate::option::Option::{self, *};
+        pub struct Repeat<A> {
+            element: A,
+        }
+
+        pub fn repeat<T>(elt: T) -> Repeat<T> {
+            Repeat { element: elt }
+        }
+
+        impl<A> Iterator for Repeat<A> {
+            type Item = A;
+
+            fn next(&mut self) -> Option<A> {
+                None
+            }
+        }
+    }
+
+    pub use self::adapters::*;
+    pub(crate) mod adapters {
+        use super::Iterator;
+        use crate::option::Option::{self, *};
+        pub struct Take<I> { pub(crate) inner: I }
+        impl<I> Iterator for Take<I> where I: Iterator {
+            type Item = <I as Iterator>::Item;
+            fn next(&mut self) -> Option<<I as Iterator>::Item> {
+                None
+            }
+        }
+        pub struct Enumerate<I> { pub(crate) inner: I }
+        impl<I> Iterator for Enumerate<I> where I: Iterator {
+            type Item = (usize, <I as Iterator>::Item);
+            fn next(&mut self) -> Option<(usize, <I as Iterator>::Item)> {
+                None
+            }
+        }
+    }
+}
+
+pub mod option {
+    pub enum Option<T> { None, Some(T)}
+}
+
+pub mod result {
+    pub enum Result<T, E> { Ok(T), Err(E) }
+}
+
+pub mod ops {
+    #[lang = "index"]
+    pub trait Index<Idx> {
+        type Output;
+        fn index(&self, index: Idx) -> &Self::Output;
+    }
+
+    impl<T> Index<usize> for [T] {
+        type Output = T;
+        fn index(&self, index: usize) -> &T {
+            loop {}
+        }
+    }
+
+    #[lang = "add"]
+    pub trait Add<Rhs = Self> {
+        type Output;
+        fn add(self, rhs: Rhs) -> Self::Output;
+    }
+
+    #[lang = "mul"]
+    pub trait Mul<Rhs = Self> {
+        type Output;
+        fn mul(self, rhs: Rhs) -> Self::Output;
+    }
+
+    #[lang = "deref"]
+    pub trait Deref {
+        type Target: ?Sized;
+        fn deref(&self) -> &Self::Target;
+    }
+
+    pub trait Try {
+        type Ok;
+        type Error;
+    }
+
+    pub enum ControlFlow<B, C = ()> {
+        Continue(C),
+        Break(B),
+    }
+
+    impl<B, C> Try for ControlFlow<B, C> {
+        type Ok = C;
+        type Error = B;
+    }
+}
+
+pub mod future {
+    #[lang = "future_trait"]
+    pub trait Future {
+        type Output;
+    }
+
+    pub struct Ready<T> { value: T }
+
+    pub fn ready<T>(value: T) -> Ready<T> {
+        Ready { value }
+    }
+
+    impl<T> Future for Ready<T> {
+        type Output = T;
+    }
+}
+
+pub mod prelude {
+    pub use crate::{convert::From, iter::{IntoIterator, Iterator}, option::Option::{self, *}};
+}
+#[prelude_import]
+pub use prelude::*;
+"#;
+
+    pub fn core_convert_From(&self) -> Option<Trait> {
+        self.find_trait("core:convert:From")
+    }
+
+    pub fn core_option_Option(&self) -> Option<Enum> {
+        self.find_enum("core:option:Option")
+    }
+
+    pub fn core_result_Result(&self) -> Option<Enum> {
+        self.find_enum("core:result:Result")
+    }
+
+    pub fn core_iter_Iterator(&self) -> Option<Trait> {
+        self.find_trait("core:iter:traits:iterator:Iterator")
+    }
+
+    pub fn core_ops_Try(&self) -> Option<Trait> {
+        self.find_trait("core:ops:Try")
+    }
+
+    pub fn core_ops_Index(&self) -> Option<Trait> {
+        self.find_trait("core:ops:Index")
+    }
+
+    /// The trait overloading the binary operator `op`, like `Add` for `+`.
+    pub fn core_ops_binary_op(&self, op: ast::BinOp) -> Option<Trait> {
+        let trait_ = match op {
+            ast::BinOp::Addition => "Add",
+            ast::BinOp::Subtraction => "Sub",
+            ast::BinOp::Multiplication => "Mul",
+            ast::BinOp::Division => "Div",
+            ast::BinOp::Remainder => "Rem",
+            ast::BinOp::LeftShift => "Shl",
+            ast::BinOp::RightShift => "Shr",
+            ast::BinOp::BitwiseAnd => "BitAnd",
+            ast::BinOp::BitwiseOr => "BitOr",
+            ast::BinOp::BitwiseXor => "BitXor",
+            _ => return None,
+        };
+        self.find_trait(&format!("core:ops:{}", trait_))
+    }
+
+    pub fn core_iter(&self) -> Option<Module> {
+        self.find_module("core:iter")
+    }
+
+    pub fn core_future_Future(&self) -> Option<Trait> {
+        self.find_trait("core:future:Future")
+    }
+
+    pub fn core_future(&self) -> Option<Module> {
+        self.find_module("core:future")
+    }
+
+    pub fn alloc_string_String(&self) -> Option<Struct> {
+        self.find_struct("alloc:string:String")
+    }
+
+    fn find_trait(&self, path: &str) -> Option<Trait> {
+        match self.find_def(path)? {
+            hir::ScopeDef::ModuleDef(hir::ModuleDef::Trait(it)) => Some(it),
+            _ => None,
+        }
+    }
+
+    fn find_struct(&self, path: &str) -> Option<Struct> {
+        match self.find_def(path)? {
+            hir::ScopeDef::ModuleDef(hir::ModuleDef::Adt(hir::Adt::Struct(it))) => Some(it),
+            _ => None,
+        }
+    }
+
+    fn find_enum(&self, path: &str) -> Option<Enum> {
+        match self.find_def(path)? {
+            hir::ScopeDef::ModuleDef(hir::ModuleDef::Adt(hir::Adt::Enum(it))) => Some(it),
+            _ => None,
+        }
+    }
+
+    fn find_module(&self, path: &str) -> Option<Module> {
+        match self.find_def(path)? {
+            hir::ScopeDef::ModuleDef(hir::ModuleDef::Module(it)) => Some(it),
+            _ => None,
+        }
+    }
+
+    fn find_def(&self, path: &str) -> Option<ScopeDef> {
+        let db = self.0.db;
+        let mut path = path.split(':');
+        let trait_ = path.next_back()?;
+        let std_crate = path.next()?;
+        let std_crate = if self
+            .1
+            .declaration_name(db)
+            .map(|name| name.to_string() == std_crate)
+            .unwrap_or(false)
+        {
+            self.1
+        } else {
+            self.1.dependencies(db).into_iter().find(|dep| dep.name.to_string() == std_crate)?.krate
+        };
+        let mut module = std_crate.root_module(db);
+        for segment in path {
+            module = module.children(db).find_map(|child| {
+                let name = child.name(db)?;
+                if name.to_string() == segment {
+                    Some(child)
+                } else {
+                    None
+                }
+            })?;
+        }
+        let def =
+            module.scope(db, None).into_iter().find(|(name, _def)| name.to_string() == trait_)?.1;
+        Some(def)
+    }
+}
@@ -0,0 +1,468 @@
+//! The inlay hints of a file, and the query caching them.
+
+mod compute;
+
+use std::{fmt, sync::Arc};
+
+use base_db::{salsa, FileId, FileRange, Upcast};
+use hir::{db::HirDatabase, ClosureStyle};
+use syntax::{SyntaxNodePtr, TextRange};
+use text_edit::TextEdit;
+
+use crate::{markup::Markup, RootDatabase};
+
+use self::compute::{assoc_shortening_defs, item_inlay_hints, AssocDefs};
+pub use self::compute::{
+    inlay_hint_at, inlay_hint_for_expr_at, inlay_hints, inlay_hints_compact, inlay_hints_count,
+    inlay_hints_in_file, inlay_hints_range, resolve_inlay_hint, CompactHints,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct InlayHintsConfig {
+    pub type_hints: bool,
+    pub parameter_hints: bool,
+    pub self_param_hints: bool,
+    /// Only hint the `self` argument of calls that pass it explicitly, like
+    /// `Type::method(&x)`, and never the receiver of `x.method()`.
+    pub self_param_hints_only_when_ufcs: bool,
+    /// Name the fields of tuple struct and tuple variant constructors, as in
+    /// `Pair(.0: a, .1: b)`. This covers constructors with a single field,
+    /// like `Some`, so it is off by default.
+    pub tuple_constructor_hints: bool,
+    pub chaining_hints: bool,
+    /// Show the success type after every `?` of a chain with several of them,
+    /// like `a?.b?.c`, rendered as chaining hints.
+    pub question_mark_chain_hints: bool,
+    /// Drop the chaining hints that repeat the label of the closest hinted
+    /// link inside them, like several links of an iterator chain all showing
+    /// `impl Iterator<Item = ()>`.
+    pub dedup_adjacent_chaining_hints: bool,
+    /// Only show a chaining hint when its type differs from the type of the
+    /// link it is called on, so that runs of `.by_ref()` show a single hint.
+    pub chaining_hints_on_change_only: bool,
+    /// Show chaining hints on struct literals, like `Foo { a: 1 }` in
+    /// `Foo { a: 1 }.method()`. Literals of structs without fields never get
+    /// one.
+    pub chaining_hints_on_record_literals: bool,
+    /// Where chaining hints are put, on the link before the newline or on the
+    /// `.` that continues the chain on the next line.
+    pub chaining_hints_anchor: ChainingHintsAnchor,
+    /// Show the error type a `?` converts to with `From`, like
+    /// `as Box<dyn Error>`, when it differs from the one it propagates.
+    pub question_mark_conversion_hints: bool,
+    /// Show the `Index::Output` type of indexing expressions, like `map[key]`
+    /// or each step of `matrix[i][j]`, unless the result is bound by a
+    /// type-hinted `let`.
+    pub index_output_hints: bool,
+    /// Show the `Output` type of binary operators overloaded with traits like
+    /// `Add`, unless it is the type of both operands. Inference doesn't type
+    /// these operators yet, so `let` bindings of the result get no type hint.
+    pub operator_hints: bool,
+    /// Show the type of explicit dereferences, like `*boxed`, which goes
+    /// through `Deref::Target` for smart pointers, unless the result is bound
+    /// by a type-hinted `let`.
+    pub deref_hints: bool,
+    /// Show the types of the arguments of calls as a tuple on their argument
+    /// list, like `(i32, &str)`. Meant for debugging coercions.
+    pub arg_list_type_hints: bool,
+    /// Show the type of unsuffixed numeric literals, like `u8` for the `5` of
+    /// `takes_u8(5)`, unless it is the `i32` or `f64` they default to.
+    pub literal_type_hints: bool,
+    /// Show the type inferred for variants of generic enums written without
+    /// their generic arguments, like `Option<i32>` for the `Some(5)` of
+    /// `foo(Some(5))`. The initializers of `let` bindings are left to their
+    /// type hint.
+    pub inferred_constructor_type_hints: bool,
+    /// Merge the hints with the same range into one, so that clients don't
+    /// render several overlapping labels. Adjustments come first, in
+    /// parentheses, as in `(&) &self`.
+    pub combine_colocated_hints: bool,
+    pub surprise_inference_hints: bool,
+    pub adjustment_hints: bool,
+    /// Flag arguments named after a different parameter of the same call
+    /// than the one they're passed to, like `x` and `y` in `foo(y, x)`.
+    pub arg_order_mismatch_hints: bool,
+    /// List the bindings a closure captures before its parameters, like
+    /// `&a &mut b |x|`. Bindings moved out of in the closure body, which make
+    /// it `FnOnce`, read `move c` rather than a borrow.
+    pub closure_capture_hints: bool,
+    /// Name the positions of tuple struct type hints after the first word of
+    /// their fields' doc comments.
+    pub tuple_struct_field_doc_hints: bool,
+    /// Spell out the lifetime of references to temporaries whose lifetime is
+    /// extended by a `let` binding, as in `let x = &make();`.
+    pub show_lifetimes_in_hints: bool,
+    /// Keep the generic arguments of types that are equal to their defaults,
+    /// like the `u8` of `Test<i32, u8>` for `struct Test<K, T = u8>`.
+    pub show_default_generic_args: bool,
+    /// Abbreviate the `Ok` type of a `Result` to `Name<…>` when it is generic
+    /// itself and the full type would exceed `max_length`, so that a long
+    /// `Result<Result<T, E1>, E2>` reads `Result<Result<…>, E2>`.
+    pub result_shortening: bool,
+    /// Hide the type hints of bindings that are `String`, `&str`, `&String`
+    /// or `&mut String`, which are rarely surprising in text-heavy code.
+    pub hide_string_type_hints: bool,
+    /// Hide the type hints of bindings initialized with a struct literal or a
+    /// `new` or `default` call that already name their type, like
+    /// `Foo::new()`. Generic types whose arguments were inferred still get one.
+    pub hide_hints_for_obvious_constructors: bool,
+    /// Hide the type hints of bindings of a single-line `let` initialized
+    /// with a path starting with their type's name, like `MyEnum::Variant`.
+    pub hide_hints_when_type_in_rhs: bool,
+    /// Show the concrete type behind a function's `impl Trait` return type in
+    /// type hints, like `Ready<i32>` for `impl Future<Output = i32>`, when it
+    /// is known and can be named.
+    pub expand_impl_trait_hints: bool,
+    /// Only produce hints inside functions whose name contains this string,
+    /// or matches it as a glob if it contains `*`.
+    pub hint_function_filter: Option<String>,
+    pub max_length: Option<usize>,
+    /// How the types of hints longer than `max_length` are cut down.
+    pub truncation_policy: TruncationPolicy,
+    /// Before cutting down types longer than `max_length`, keep only the
+    /// first trait of trait objects, as in `Box<dyn Display + …>`.
+    pub collapse_dyn_bounds: bool,
+    /// Write at most this many generic arguments of each type, collapsing the
+    /// rest, as in `Tuple<A, B, …>`.
+    pub max_generic_args: Option<usize>,
+    /// Write only the outermost type constructor of types, with `_` for
+    /// their generic arguments, as in `Vec<_>`.
+    pub minimal_type_hints: bool,
+    /// Write projections that inference leaves in types, like the
+    /// `<Square as Shape>::Area` in the bounds of an `impl Trait`, as the type
+    /// they normalize to where it is known.
+    pub normalize_projections: bool,
+    /// How the types of closures are written, like `|…| -> i32` or
+    /// `impl Fn(i32) -> i32`. With `ClosureStyle::Hide`, bindings of a
+    /// closure get no type hint at all.
+    pub closure_style: ClosureStyle,
+    /// Leave the labels of type-like hints empty, to be filled in on demand
+    /// with `resolve_inlay_hint`.
+    pub lazy_labels: bool,
+    pub hint_tooltips: bool,
+    /// Give every hint an `id` that stays the same while the syntax tree
+    /// around the hinted node keeps its shape.
+    pub hint_ids: bool,
+    /// Text put around the labels of each kind of hints.
+    pub render_templates: HintRenderTemplates,
+}
+
+impl Default for InlayHintsConfig {
+    fn default() -> Self {
+        Self {
+            type_hints: true,
+            parameter_hints: true,
+            self_param_hints: true,
+            self_param_hints_only_when_ufcs: true,
+            tuple_constructor_hints: false,
+            chaining_hints: true,
+            question_mark_chain_hints: false,
+            dedup_adjacent_chaining_hints: false,
+            chaining_hints_on_change_only: false,
+            chaining_hints_on_record_literals: false,
+            chaining_hints_anchor: ChainingHintsAnchor::Receiver,
+            question_mark_conversion_hints: false,
+            index_output_hints: false,
+            operator_hints: false,
+            deref_hints: false,
+            arg_list_type_hints: false,
+            literal_type_hints: false,
+            inferred_constructor_type_hints: false,
+            combine_colocated_hints: false,
+            surprise_inference_hints: false,
+            adjustment_hints: false,
+            arg_order_mismatch_hints: false,
+            closure_capture_hints: false,
+            tuple_struct_field_doc_hints: false,
+            show_lifetimes_in_hints: false,
+            show_default_generic_args: false,
+            result_shortening: false,
+            hide_string_type_hints: false,
+            hide_hints_for_obvious_constructors: false,
+            hide_hints_when_type_in_rhs: false,
+            expand_impl_trait_hints: false,
+            hint_function_filter: None,
+            max_length: None,
+            truncation_policy: TruncationPolicy::Chars,
+            collapse_dyn_bounds: false,
+            max_generic_args: None,
+            minimal_type_hints: false,
+            normalize_projections: false,
+            closure_style: ClosureStyle::RANotation,
+            lazy_labels: false,
+            hint_tooltips: false,
+            hint_ids: false,
+            render_templates: HintRenderTemplates::EMPTY,
+        }
+    }
+}
+
+impl InlayHintsConfig {
+    /// Whether any kind of hint is enabled at all. Options that only tweak
+    /// the hints of another kind, like `self_param_hints`, don't count.
+    pub fn any_kind_enabled(&self) -> bool {
+        self.type_hints
+            || self.parameter_hints
+            || self.chaining_hints
+            || self.question_mark_chain_hints
+            || self.surprise_inference_hints
+            || self.adjustment_hints
+            || self.question_mark_conversion_hints
+            || self.index_output_hints
+            || self.operator_hints
+            || self.deref_hints
+            || self.arg_list_type_hints
+            || self.literal_type_hints
+            || self.inferred_constructor_type_hints
+            || self.arg_order_mismatch_hints
+            || self.closure_capture_hints
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ChainingHintsAnchor {
+    /// On the link the hint is about, which ends before the newline.
+    Receiver,
+    /// On the `.` starting the next line, for clients that render hints
+    /// after the end of their range.
+    AfterDot,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TruncationPolicy {
+    /// Stop writing the type once it is `max_length` characters long.
+    Chars,
+    /// Cut the type at the start of one of its generic arguments, and close
+    /// the brackets left open.
+    Delimiters,
+    /// Collapse the most deeply nested generic arguments first, until the
+    /// type fits.
+    Depth,
+}
+
+/// The text to put before and after the labels of one kind of hints, like
+/// `": "` before type hints.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct HintTemplate {
+    pub prefix: String,
+    pub suffix: String,
+}
+
+impl HintTemplate {
+    pub const EMPTY: HintTemplate = HintTemplate { prefix: String::new(), suffix: String::new() };
+}
+
+/// Decorations applied to the labels of every kind of hints, for clients
+/// that show labels as they are. The empty templates leave labels alone.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct HintRenderTemplates {
+    pub type_hints: HintTemplate,
+    pub parameter_hints: HintTemplate,
+    pub chaining_hints: HintTemplate,
+    pub surprise_inference_hints: HintTemplate,
+    pub adjustment_hints: HintTemplate,
+    pub arg_order_mismatch_hints: HintTemplate,
+    pub closure_capture_hints: HintTemplate,
+}
+
+impl HintRenderTemplates {
+    pub const EMPTY: HintRenderTemplates = HintRenderTemplates {
+        type_hints: HintTemplate::EMPTY,
+        parameter_hints: HintTemplate::EMPTY,
+        chaining_hints: HintTemplate::EMPTY,
+        surprise_inference_hints: HintTemplate::EMPTY,
+        adjustment_hints: HintTemplate::EMPTY,
+        arg_order_mismatch_hints: HintTemplate::EMPTY,
+        closure_capture_hints: HintTemplate::EMPTY,
+    };
+
+    fn template(&self, kind: InlayKind) -> &HintTemplate {
+        match kind {
+            InlayKind::TypeHint => &self.type_hints,
+            InlayKind::ParameterHint => &self.parameter_hints,
+            InlayKind::ChainingHint => &self.chaining_hints,
+            InlayKind::SurpriseInferenceHint => &self.surprise_inference_hints,
+            InlayKind::AdjustmentHint => &self.adjustment_hints,
+            InlayKind::ArgOrderMismatchHint => &self.arg_order_mismatch_hints,
+            InlayKind::ClosureCaptureHint => &self.closure_capture_hints,
+        }
+    }
+
+    /// Wraps the label of `hint` in the template of its kind. Empty labels,
+    /// still to be resolved, are left empty.
+    pub fn apply(&self, hint: &mut InlayHint) {
+        if hint.label.is_empty() {
+            return;
+        }
+        let template = self.template(hint.kind);
+        let mut label = InlayHintLabel::default();
+        label.push_str(&template.prefix);
+        label.append(std::mem::take(&mut hint.label));
+        label.push_str(&template.suffix);
+        hint.label = label;
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum InlayKind {
+    TypeHint,
+    ParameterHint,
+    ChainingHint,
+    SurpriseInferenceHint,
+    AdjustmentHint,
+    ArgOrderMismatchHint,
+    ClosureCaptureHint,
+}
+
+impl InlayKind {
+    pub const ALL: [InlayKind; 7] = [
+        InlayKind::TypeHint,
+        InlayKind::ParameterHint,
+        InlayKind::ChainingHint,
+        InlayKind::SurpriseInferenceHint,
+        InlayKind::AdjustmentHint,
+        InlayKind::ArgOrderMismatchHint,
+        InlayKind::ClosureCaptureHint,
+    ];
+
+    /// Hints shown after the code they describe are padded on the left, and
+    /// parameter names and captures, which precede what they describe, on the
+    /// right. Adjustments hug the expression they apply to.
+    pub fn padding(self) -> (bool, bool) {
+        match self {
+            InlayKind::TypeHint | InlayKind::ChainingHint | InlayKind::SurpriseInferenceHint => {
+                (true, false)
+            }
+            InlayKind::ParameterHint
+            | InlayKind::ArgOrderMismatchHint
+            | InlayKind::ClosureCaptureHint => (false, true),
+            InlayKind::AdjustmentHint => (false, false),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InlayHint {
+    pub range: TextRange,
+    pub kind: InlayKind,
+    pub label: InlayHintLabel,
+    /// Markdown shown when hovering the hint, if `hint_tooltips` is enabled.
+    pub tooltip: Option<Markup>,
+    /// Identifies the hint across recomputations, if `hint_ids` is enabled.
+    pub id: Option<u64>,
+    /// Whether the editor should leave some space between the hint and the
+    /// code before it, as in `x: i32`.
+    pub pad_left: bool,
+    /// Whether the editor should leave some space between the hint and the
+    /// code after it, as in `name: arg`.
+    pub pad_right: bool,
+    /// Writes the hint into the source, like `: i32` after `let x`. Uses
+    /// the full type even when the label is truncated.
+    pub text_edit: Option<TextEdit>,
+}
+
+/// A hint label split into parts, so that editors can make each type named
+/// in it clickable on its own.
+#[derive(Default, Clone, PartialEq, Eq)]
+pub struct InlayHintLabel {
+    pub parts: Vec<InlayHintLabelPart>,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct InlayHintLabelPart {
+    pub text: String,
+    /// Definition of the type this part names.
+    pub goto: Option<FileRange>,
+}
+
+impl InlayHintLabel {
+    pub fn is_empty(&self) -> bool {
+        self.parts.iter().all(|part| part.text.is_empty())
+    }
+
+    pub fn push_str(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        match self.parts.last_mut() {
+            Some(InlayHintLabelPart { text: last, goto: None }) => last.push_str(text),
+            _ => self.parts.push(InlayHintLabelPart { text: text.to_string(), goto: None }),
+        }
+    }
+
+    pub fn append(&mut self, other: InlayHintLabel) {
+        for part in other.parts {
+            match part.goto {
+                Some(_) => self.parts.push(part),
+                None => self.push_str(&part.text),
+            }
+        }
+    }
+}
+
+impl From<&str> for InlayHintLabel {
+    fn from(text: &str) -> InlayHintLabel {
+        InlayHintLabel { parts: vec![InlayHintLabelPart { text: text.to_string(), goto: None }] }
+    }
+}
+
+impl From<String> for InlayHintLabel {
+    fn from(text: String) -> InlayHintLabel {
+        InlayHintLabel { parts: vec![InlayHintLabelPart { text, goto: None }] }
+    }
+}
+
+impl fmt::Display for InlayHintLabel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.parts.iter().try_for_each(|part| f.write_str(&part.text))
+    }
+}
+
+impl fmt::Debug for InlayHintLabel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Keep plain labels readable in test snapshots.
+        if self.parts.iter().all(|part| part.goto.is_none()) {
+            fmt::Debug::fmt(&self.to_string(), f)
+        } else {
+            f.debug_list().entries(&self.parts).finish()
+        }
+    }
+}
+
+impl fmt::Debug for InlayHintLabelPart {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.goto {
+            None => fmt::Debug::fmt(&self.text, f),
+            Some(goto) => f
+                .debug_struct("InlayHintLabelPart")
+                .field("text", &self.text)
+                .field("goto", goto)
+                .finish(),
+        }
+    }
+}
+
+/// How many items the hints of a file are expected to be split into, to keep
+/// the hints of about as many files as there are parse trees kept.
+pub(crate) const ITEMS_PER_FILE: usize = 32;
+
+#[salsa::query_group(InlayHintsDatabaseStorage)]
+pub trait InlayHintsDatabase: HirDatabase + Upcast<RootDatabase> {
+    /// The hints of the top-level `item` of the file, before the options that
+    /// only rearrange or decorate them are applied. Hints are cached per item
+    /// so that they can still be computed in parallel, and the least recently
+    /// used items are dropped.
+    ///
+    /// The item is found again in the parse of the file, so changing the file
+    /// recomputes its items, while other changes only revalidate them.
+    fn item_inlay_hints(
+        &self,
+        file_id: FileId,
+        item: SyntaxNodePtr,
+        config: InlayHintsConfig,
+    ) -> Arc<Vec<InlayHint>>;
+
+    /// The traits and associated types of the shortened type hints, as seen
+    /// from `krate`.
+    fn assoc_shortening_defs(&self, krate: hir::Crate) -> Arc<Vec<Option<AssocDefs>>>;
+}
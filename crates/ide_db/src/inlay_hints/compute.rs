@@ -0,0 +1,2884 @@
+//! Computes the inlay hints of a file.
+
+use std::{
+    cell::RefCell,
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+use base_db::{
+    salsa::{self, ParallelDatabase},
+    FileId, FileRange, SourceDatabase,
+};
+use hir::{AsAssocItem, AsName, ClosureStyle, HasAttrs, HasSource, HirDisplay, Semantics};
+use rayon::prelude::*;
+use rustc_hash::{FxHashMap, FxHashSet, FxHasher};
+use stdx::{format_to, to_lower_snake_case};
+use syntax::{
+    ast::{self, ArgListOwner, AstNode, GenericParamsOwner},
+    match_ast, Direction, NodeOrToken, SyntaxKind, SyntaxNode, SyntaxNodePtr, SyntaxToken,
+    TextRange, TextSize, T,
+};
+use text_edit::TextEdit;
+
+use crate::{
+    helpers::FamousDefs,
+    inlay_hints::{
+        ChainingHintsAnchor, HintRenderTemplates, InlayHint, InlayHintLabel, InlayHintLabelPart,
+        InlayHintsConfig, InlayHintsDatabase, InlayKind, TruncationPolicy,
+    },
+    markup::Markup,
+    RootDatabase,
+};
+use ast::NameOwner;
+use either::Either;
+
+pub fn inlay_hints(
+    db: &RootDatabase,
+    file_id: FileId,
+    config: &InlayHintsConfig,
+) -> Vec<InlayHint> {
+    inlay_hints_range(db, file_id, None, config)
+}
+
+/// Computes inlay hints only for the nodes intersecting `range`, or for the
+/// whole file if `range` is `None`.
+///
+/// Chaining hints are produced for every link of a chain that intersects
+/// `range`, even when the link itself starts above it.
+///
+/// The hints are sorted by the start and then the end of their range, and
+/// then by kind, so a chain's links come innermost first. Exact duplicates,
+/// with the same range, kind and label, are dropped.
+pub fn inlay_hints_range(
+    db: &RootDatabase,
+    file_id: FileId,
+    range: Option<TextRange>,
+    config: &InlayHintsConfig,
+) -> Vec<InlayHint> {
+    let file = db.parse(file_id).tree();
+    inlay_hints_in_file(db, file_id, &file, range, config)
+}
+
+/// Like `inlay_hints_range`, for `file`, the already parsed tree of `file_id`,
+/// so that a request running several features together parses it once.
+pub fn inlay_hints_in_file(
+    db: &RootDatabase,
+    file_id: FileId,
+    file: &ast::SourceFile,
+    range: Option<TextRange>,
+    config: &InlayHintsConfig,
+) -> Vec<InlayHint> {
+    let _p = profile::span("inlay_hints");
+    if !config.any_kind_enabled() {
+        return Vec::new();
+    }
+    // The options applied below don't change which hints are computed.
+    let compute_config = InlayHintsConfig {
+        dedup_adjacent_chaining_hints: false,
+        chaining_hints_anchor: ChainingHintsAnchor::Receiver,
+        combine_colocated_hints: false,
+        hint_ids: false,
+        render_templates: HintRenderTemplates::EMPTY,
+        ..config.clone()
+    };
+    let res = match range {
+        Some(range) => compute_hints(db, file_id, file, Some(range), &compute_config),
+        None => item_hints(db, file_id, file, &compute_config),
+    };
+    finish_hints(res, file, config)
+}
+
+/// Counts the hints `inlay_hints` returns, without rendering their labels
+/// unless some are compared to drop repeated ones.
+pub fn inlay_hints_count(db: &RootDatabase, file_id: FileId, config: &InlayHintsConfig) -> usize {
+    let _p = profile::span("inlay_hints_count");
+    if !config.any_kind_enabled() {
+        return 0;
+    }
+    let config = InlayHintsConfig {
+        hint_tooltips: false,
+        hint_ids: false,
+        render_templates: HintRenderTemplates::EMPTY,
+        ..config.clone()
+    };
+    let file = db.parse(file_id).tree();
+    if !config.dedup_adjacent_chaining_hints {
+        let lazy_config = InlayHintsConfig { lazy_labels: true, ..config.clone() };
+        // Hints without labels aren't worth keeping in the database.
+        let mut res = compute_hints(db, file_id, &file, None, &lazy_config);
+        // `finish_hints` drops repeated hints by comparing their labels, so
+        // hints of one kind at the same range, left without a label, could
+        // count as one. Unless they get combined anyway, those are counted
+        // with their labels.
+        res.sort_by_key(|hint| (hint.range.start(), hint.range.end(), hint.kind));
+        let ambiguous = res.windows(2).any(|it| {
+            it[0].range == it[1].range
+                && it[0].kind == it[1].kind
+                && (it[0].label.is_empty() || it[1].label.is_empty())
+        });
+        if config.lazy_labels || config.combine_colocated_hints || !ambiguous {
+            return finish_hints(res, &file, &lazy_config).len();
+        }
+    }
+    let res = compute_hints(db, file_id, &file, None, &config);
+    finish_hints(res, &file, &config).len()
+}
+
+/// The hints of a file in a compact binary form, for clients that fetch the
+/// hints of many files at once.
+///
+/// `data` starts with the number of hints, followed by each hint in the order
+/// `inlay_hints` returns them, as:
+///
+/// * the distance from the start of the previous hint's range, or from the
+///   start of the file for the first one,
+/// * the length of the range,
+/// * a byte with the index of the kind in `InlayKind::ALL` in its low four
+///   bits, `pad_left` in the fifth and `pad_right` in the sixth,
+/// * the index of the label in `labels`, which holds each distinct label
+///   once.
+///
+/// Numbers are LEB128 varints. Tooltips, ids, text edits and the targets of
+/// label parts are left out.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CompactHints {
+    pub data: Vec<u8>,
+    pub labels: Vec<String>,
+}
+
+impl CompactHints {
+    pub fn encode(hints: &[InlayHint]) -> CompactHints {
+        let mut res = CompactHints::default();
+        let mut label_ids = FxHashMap::default();
+        let mut prev_start = 0;
+        write_varint(&mut res.data, hints.len() as u32);
+        for hint in hints {
+            let start = u32::from(hint.range.start());
+            write_varint(&mut res.data, start - prev_start);
+            write_varint(&mut res.data, u32::from(hint.range.len()));
+            let kind = InlayKind::ALL.iter().position(|&kind| kind == hint.kind).unwrap() as u8;
+            res.data.push(kind | (hint.pad_left as u8) << 4 | (hint.pad_right as u8) << 5);
+            let label = hint.label.to_string();
+            let labels = &mut res.labels;
+            let label_id = *label_ids.entry(label.clone()).or_insert_with(|| {
+                labels.push(label);
+                labels.len() as u32 - 1
+            });
+            write_varint(&mut res.data, label_id);
+            prev_start = start;
+        }
+        res
+    }
+
+    /// Returns `None` if `data` isn't a valid encoding.
+    pub fn decode(&self) -> Option<Vec<InlayHint>> {
+        let mut data = self.data.iter().copied();
+        let len = read_varint(&mut data)?;
+        let mut res = Vec::with_capacity(len as usize);
+        let mut start = 0u32;
+        for _ in 0..len {
+            start = start.checked_add(read_varint(&mut data)?)?;
+            let end = start.checked_add(read_varint(&mut data)?)?;
+            let flags = data.next()?;
+            let kind = *InlayKind::ALL.get(usize::from(flags & 0xf))?;
+            let label = self.labels.get(read_varint(&mut data)? as usize)?;
+            res.push(InlayHint {
+                range: TextRange::new(start.into(), end.into()),
+                kind,
+                label: label.as_str().into(),
+                tooltip: None,
+                id: None,
+                pad_left: flags & 0x10 != 0,
+                pad_right: flags & 0x20 != 0,
+                text_edit: None,
+            });
+        }
+        if data.next().is_some() {
+            return None;
+        }
+        Some(res)
+    }
+}
+
+fn write_varint(buf: &mut Vec<u8>, mut value: u32) {
+    while value >= 0x80 {
+        buf.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+fn read_varint(bytes: &mut impl Iterator<Item = u8>) -> Option<u32> {
+    let mut value = 0u32;
+    for shift in (0..32).step_by(7) {
+        let byte = bytes.next()?;
+        value |= u32::from(byte & 0x7f).checked_shl(shift)?;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// Encodes the hints `inlay_hints` returns, sharing their cached hints, so
+/// the options for the parts left out of the encoding are kept to avoid
+/// recomputing them.
+pub fn inlay_hints_compact(
+    db: &RootDatabase,
+    file_id: FileId,
+    config: &InlayHintsConfig,
+) -> CompactHints {
+    let _p = profile::span("inlay_hints_compact");
+    CompactHints::encode(&inlay_hints(db, file_id, config))
+}
+
+/// Applies the options that rearrange or decorate the computed hints.
+fn finish_hints(
+    mut res: Vec<InlayHint>,
+    file: &ast::SourceFile,
+    config: &InlayHintsConfig,
+) -> Vec<InlayHint> {
+    if config.dedup_adjacent_chaining_hints && !config.lazy_labels {
+        dedup_adjacent_chaining_hints(&mut res);
+    }
+    if config.chaining_hints_anchor == ChainingHintsAnchor::AfterDot {
+        anchor_chaining_hints_after_dot(file.syntax(), &mut res);
+    }
+    for hint in &mut res {
+        config.render_templates.apply(hint);
+    }
+    if config.combine_colocated_hints {
+        combine_colocated_hints(&mut res);
+    }
+    res.sort_by_key(|hint| (hint.range.start(), hint.range.end(), hint.kind));
+    res.dedup_by(|a, b| a.range == b.range && a.kind == b.kind && a.label == b.label);
+    if config.hint_ids {
+        assign_ids(file.syntax(), &mut res);
+    }
+    res
+}
+
+fn compute_hints(
+    db: &RootDatabase,
+    file_id: FileId,
+    file: &ast::SourceFile,
+    range: Option<TextRange>,
+    config: &InlayHintsConfig,
+) -> Vec<InlayHint> {
+    // The top-level items are traversed in parallel, and their hints are
+    // concatenated in source order. Syntax nodes can't be sent to other
+    // threads, so each task rebuilds the tree from its green node, which
+    // can, and finds its item again in there.
+    let green = file.syntax().green().clone();
+    let items = file.syntax().children().map(|it| SyntaxNodePtr::new(&it)).collect::<Vec<_>>();
+    let state = (Snap(db.snapshot()), PassCache::default());
+    items
+        .par_iter()
+        .map_with(state, |(snap, cache), item| {
+            // Spans don't cross threads, so the spans of each hint function
+            // are nested under the item's own span.
+            let _p = profile::span("inlay_hints_item");
+            let sema = Semantics::new(&*snap.0);
+            let file = ast::SourceFile::cast(SyntaxNode::new_root(green.clone())).unwrap();
+            sema.cache_parsed(file_id, &file);
+            let mut acc = Vec::new();
+            let item = item.to_node(file.syntax());
+            collect_subtree_hints(&mut acc, &sema, cache, config, item, range);
+            acc
+        })
+        .collect::<Vec<_>>()
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+/// The hints of the whole file, from the `item_inlay_hints` query, which
+/// keeps them until a change that may affect them. Each item's hints are
+/// fetched on its own snapshot, as the query can't spread its work over
+/// several threads itself.
+fn item_hints(
+    db: &RootDatabase,
+    file_id: FileId,
+    file: &ast::SourceFile,
+    config: &InlayHintsConfig,
+) -> Vec<InlayHint> {
+    let items = file.syntax().children().map(|it| SyntaxNodePtr::new(&it)).collect::<Vec<_>>();
+    items
+        .par_iter()
+        .map_with(Snap(db.snapshot()), |snap, item| {
+            snap.0.item_inlay_hints(file_id, item.clone(), config.clone())
+        })
+        .collect::<Vec<_>>()
+        .iter()
+        .flat_map(|hints| hints.iter().cloned())
+        .collect()
+}
+
+pub(super) fn item_inlay_hints(
+    db: &dyn InlayHintsDatabase,
+    file_id: FileId,
+    item: SyntaxNodePtr,
+    config: InlayHintsConfig,
+) -> Arc<Vec<InlayHint>> {
+    let _p = profile::span("item_inlay_hints");
+    let sema = Semantics::new(db.upcast());
+    let file = sema.parse(file_id);
+    let mut acc = Vec::new();
+    // The pointer may be stale for a query kept by the LRU past a change of
+    // the file, which no caller asks for anymore.
+    if let Some(item) = file.syntax().children().find(|it| SyntaxNodePtr::new(it) == item) {
+        collect_subtree_hints(&mut acc, &sema, &PassCache::default(), &config, item, None);
+    }
+    Arc::new(acc)
+}
+
+fn dedup_adjacent_chaining_hints(hints: &mut Vec<InlayHint>) {
+    // All the links of a chain start where its innermost receiver does, so
+    // sorting by range puts every link right after the one it contains.
+    let mut links = (0..hints.len())
+        .filter(|&idx| hints[idx].kind == InlayKind::ChainingHint)
+        .collect::<Vec<_>>();
+    links.sort_by_key(|&idx| (hints[idx].range.start(), hints[idx].range.end()));
+    let redundant = links
+        .windows(2)
+        .filter(|pair| {
+            let (inner, outer) = (&hints[pair[0]], &hints[pair[1]]);
+            inner.range.start() == outer.range.start() && inner.label == outer.label
+        })
+        .map(|pair| pair[1])
+        .collect::<FxHashSet<_>>();
+
+    let mut idx = 0;
+    hints.retain(|_| {
+        idx += 1;
+        !redundant.contains(&(idx - 1))
+    });
+}
+
+/// Moves the chaining hints from their link to the `.` after it. They are
+/// computed on the link, and only moved once done with their ranges.
+fn anchor_chaining_hints_after_dot(root: &SyntaxNode, hints: &mut [InlayHint]) {
+    for hint in hints.iter_mut().filter(|hint| hint.kind == InlayKind::ChainingHint) {
+        let node = match root.covering_element(hint.range) {
+            NodeOrToken::Node(it) => it,
+            NodeOrToken::Token(_) => continue,
+        };
+        let dot = node
+            .ancestors()
+            .take_while(|it| it.text_range() == hint.range)
+            .find_map(|it| chain_continuation_dot(&it));
+        if let Some(dot) = dot {
+            hint.range = dot.text_range();
+        }
+    }
+}
+
+/// The link of the chain whose hint `AfterDot` puts on the `.` at `range`.
+fn chain_link_before_dot(root: &SyntaxNode, range: TextRange) -> Option<SyntaxNode> {
+    let dot = root.covering_element(range).into_token().filter(|it| it.kind() == T![.])?;
+    let link = dot.siblings_with_tokens(Direction::Prev).find_map(NodeOrToken::into_node)?;
+    if chain_continuation_dot(&link)? != dot {
+        return None;
+    }
+    Some(link)
+}
+
+fn combine_colocated_hints(hints: &mut Vec<InlayHint>) {
+    let mut groups: Vec<Vec<InlayHint>> = Vec::new();
+    let mut group_of_range = FxHashMap::default();
+    for hint in hints.drain(..) {
+        let idx = *group_of_range.entry(hint.range).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[idx].push(hint);
+    }
+    hints.extend(groups.into_iter().map(combine_hints));
+}
+
+/// Merges the hints into the first one that isn't an adjustment, which keeps
+/// its kind, so that clients still render the result in the same place.
+fn combine_hints(group: Vec<InlayHint>) -> InlayHint {
+    let (mut adjustments, mut rest): (Vec<_>, Vec<_>) =
+        group.into_iter().partition(|hint| hint.kind == InlayKind::AdjustmentHint);
+    if rest.is_empty() {
+        rest = adjustments.split_off(adjustments.len() - 1);
+    }
+
+    let mut label = InlayHintLabel::default();
+    for adjustment in adjustments {
+        label.push_str("(");
+        label.append(adjustment.label);
+        label.push_str(") ");
+    }
+    let mut rest = rest.into_iter();
+    let mut res = rest.next().unwrap();
+    label.append(res.label);
+    for hint in rest {
+        label.push_str(" ");
+        label.append(hint.label);
+        if res.tooltip.is_none() {
+            res.tooltip = hint.tooltip;
+        }
+    }
+    res.label = label;
+    res
+}
+
+/// Need to wrap Snapshot to provide `Clone` impl for `map_with`
+struct Snap<DB>(DB);
+impl<DB: ParallelDatabase> Clone for Snap<salsa::Snapshot<DB>> {
+    fn clone(&self) -> Snap<salsa::Snapshot<DB>> {
+        Snap(self.0.snapshot())
+    }
+}
+
+fn collect_subtree_hints(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<RootDatabase>,
+    cache: &PassCache,
+    config: &InlayHintsConfig,
+    root: SyntaxNode,
+    range: Option<TextRange>,
+) {
+    // Same order as `descendants()`, but lets us skip subtrees outside of `range`.
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        let in_range = match range {
+            Some(range) => range.intersect(node.text_range()).is_some(),
+            None => true,
+        };
+        if !in_range && !is_chain_receiver(&node) {
+            continue;
+        }
+        // The fragments bound by a `macro_rules!` body are untyped, so
+        // nothing in there can get a meaningful hint.
+        if is_macro_rules_definition(&node) {
+            continue;
+        }
+        stack.extend(node.children().collect::<Vec<_>>().into_iter().rev());
+        collect_hints(acc, sema, cache, config, node, !in_range);
+    }
+}
+
+fn is_macro_rules_definition(node: &SyntaxNode) -> bool {
+    match ast::MacroCall::cast(node.clone()) {
+        Some(call) => call.is_macro_rules().is_some(),
+        None => false,
+    }
+}
+
+/// Computes the hint with the given `range` and `kind`, which was returned
+/// with an empty label because of `InlayHintsConfig::lazy_labels`.
+///
+/// The hint is exactly the one `inlay_hints` would have produced eagerly.
+pub fn resolve_inlay_hint(
+    db: &RootDatabase,
+    file_id: FileId,
+    range: TextRange,
+    kind: InlayKind,
+    config: &InlayHintsConfig,
+) -> Option<InlayHint> {
+    let _p = profile::span("resolve_inlay_hint");
+    let sema = Semantics::new(db);
+    let file = sema.parse(file_id);
+    let cache = PassCache::default();
+    let config = InlayHintsConfig { lazy_labels: false, ..config.clone() };
+    let after_dot = config.chaining_hints_anchor == ChainingHintsAnchor::AfterDot;
+    let anchor = range;
+    let range = match chain_link_before_dot(file.syntax(), range) {
+        Some(link) if after_dot && kind == InlayKind::ChainingHint => link.text_range(),
+        _ => range,
+    };
+
+    let node = match file.syntax().covering_element(range) {
+        NodeOrToken::Node(it) => it,
+        NodeOrToken::Token(it) => it.parent(),
+    };
+    // Some hints are attached to a node larger than their range, like
+    // bindings with a subpattern, hinted on their name alone, or `?`
+    // conversions, hinted on the `?` token.
+    let mut res = Vec::new();
+    for node in node.ancestors() {
+        collect_hints(&mut res, &sema, &cache, &config, node, false);
+    }
+    if after_dot {
+        anchor_chaining_hints_after_dot(file.syntax(), &mut res);
+    }
+    for hint in &mut res {
+        config.render_templates.apply(hint);
+    }
+    if config.hint_ids {
+        assign_ids(file.syntax(), &mut res);
+    }
+    res.into_iter().find(|hint| hint.range == anchor && hint.kind == kind)
+}
+
+/// Returns the hint with the smallest range containing `offset`, the same one
+/// `inlay_hints` would produce for it.
+///
+/// Every hint covering `offset` is attached to an ancestor of the token at
+/// `offset`, so only those nodes are visited.
+pub fn inlay_hint_at(
+    db: &RootDatabase,
+    file_id: FileId,
+    offset: TextSize,
+    config: &InlayHintsConfig,
+) -> Option<InlayHint> {
+    let _p = profile::span("inlay_hint_at");
+    let sema = Semantics::new(db);
+    let cache = PassCache::default();
+    let file = sema.parse(file_id);
+    let token = file.syntax().token_at_offset(offset).right_biased()?;
+
+    let mut res = Vec::new();
+    // Outermost first, so that ties are broken like in the full traversal.
+    let ancestors = token.parent().ancestors().collect::<Vec<_>>();
+    for node in ancestors.into_iter().rev() {
+        collect_hints(&mut res, &sema, &cache, config, node, false);
+    }
+    if config.chaining_hints_anchor == ChainingHintsAnchor::AfterDot {
+        // The `.` is not in the link whose hint it holds.
+        if let Some(link) = chain_link_before_dot(file.syntax(), token.text_range()) {
+            let range = link.text_range();
+            for node in link.descendants().take_while(|it| it.text_range() == range) {
+                collect_hints(&mut res, &sema, &cache, config, node, true);
+            }
+        }
+        anchor_chaining_hints_after_dot(file.syntax(), &mut res);
+    }
+    res.retain(|hint| hint.range.contains(offset));
+    for hint in &mut res {
+        config.render_templates.apply(hint);
+    }
+    if config.hint_ids {
+        assign_ids(file.syntax(), &mut res);
+    }
+    let smallest = res.iter().map(|hint| hint.range.len()).min()?;
+    res.into_iter().find(|hint| hint.range.len() == smallest)
+}
+
+/// Synthesizes a type hint for the innermost expression containing `offset`,
+/// whether or not `inlay_hints` would hint it.
+pub fn inlay_hint_for_expr_at(
+    db: &RootDatabase,
+    file_id: FileId,
+    offset: TextSize,
+    config: &InlayHintsConfig,
+) -> Option<InlayHint> {
+    let _p = profile::span("inlay_hint_for_expr_at");
+    let sema = Semantics::new(db);
+    let cache = PassCache::default();
+    let config = InlayHintsConfig { lazy_labels: false, ..config.clone() };
+    let file = sema.parse(file_id);
+    let token = file.syntax().token_at_offset(offset).right_biased()?;
+    let expr = token.parent().ancestors().find_map(ast::Expr::cast)?;
+
+    let ty = cache.type_of_expr(&sema, &expr)?;
+    if ty.is_unknown() {
+        return None;
+    }
+    let mut hint = InlayHint {
+        range: expr.syntax().text_range(),
+        kind: InlayKind::TypeHint,
+        label: type_label(&sema, &cache, &config, &ty),
+        tooltip: type_tooltip(&sema, &config, &ty),
+        pad_left: false,
+        pad_right: false,
+        text_edit: None,
+        id: None,
+    };
+    config.render_templates.apply(&mut hint);
+    if config.hint_ids {
+        assign_ids(file.syntax(), std::slice::from_mut(&mut hint));
+    }
+    Some(hint)
+}
+
+/// Derives hint ids from the path of the hinted node in the tree rather than
+/// from its offset, so that edits elsewhere don't change them.
+///
+/// Each step of the path is the node kind and the number of preceding
+/// siblings of the same kind, so only edits that add or remove such
+/// siblings along the path invalidate the id.
+fn assign_ids(root: &SyntaxNode, hints: &mut [InlayHint]) {
+    for hint in hints {
+        let node = match root.covering_element(hint.range) {
+            NodeOrToken::Node(it) => it,
+            NodeOrToken::Token(it) => it.parent(),
+        };
+        let node =
+            node.ancestors().take_while(|it| it.text_range() == hint.range).last().unwrap_or(node);
+
+        let mut hasher = FxHasher::default();
+        hint.kind.hash(&mut hasher);
+        for node in node.ancestors() {
+            let same_kind_before = node
+                .siblings(Direction::Prev)
+                .skip(1)
+                .filter(|it| it.kind() == node.kind())
+                .count();
+            node.kind().hash(&mut hasher);
+            same_kind_before.hash(&mut hasher);
+        }
+        hint.id = Some(hasher.finish());
+    }
+}
+
+/// Collects the hints attached to `node` itself, not to its descendants.
+fn collect_hints(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<RootDatabase>,
+    cache: &PassCache,
+    config: &InlayHintsConfig,
+    node: SyntaxNode,
+    chaining_only: bool,
+) {
+    if let Some(filter) = &config.hint_function_filter {
+        let name = node.ancestors().find_map(ast::Fn::cast).and_then(|it| it.name());
+        match name {
+            Some(name) if matches_function_filter(filter, name.text()) => (),
+            _ => return,
+        }
+    }
+    let start = acc.len();
+    if let Some(expr) = ast::Expr::cast(node.clone()) {
+        get_chaining_hints(acc, sema, cache, config, expr);
+    }
+    if !chaining_only {
+        collect_node_hints(acc, sema, cache, config, node);
+    }
+    for hint in &mut acc[start..] {
+        let (pad_left, pad_right) = hint.kind.padding();
+        hint.pad_left = pad_left;
+        hint.pad_right = pad_right;
+    }
+}
+
+fn collect_node_hints(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<RootDatabase>,
+    cache: &PassCache,
+    config: &InlayHintsConfig,
+    node: SyntaxNode,
+) {
+    match_ast! {
+        match node {
+            ast::CallExpr(it) => {
+                get_param_name_hints(acc, sema, cache, config, ast::Expr::from(it.clone()));
+                get_arg_order_mismatch_hints(acc, sema, cache, config, ast::Expr::from(it.clone()));
+                get_adjustment_hints(acc, sema, cache, config, ast::Expr::from(it.clone()));
+                get_inferred_constructor_hints(acc, sema, cache, config, ast::Expr::from(it));
+            },
+            ast::PathExpr(it) => {
+                get_inferred_constructor_hints(acc, sema, cache, config, ast::Expr::from(it));
+            },
+            ast::MethodCallExpr(it) => {
+                get_param_name_hints(acc, sema, cache, config, ast::Expr::from(it.clone()));
+                get_arg_order_mismatch_hints(acc, sema, cache, config, ast::Expr::from(it.clone()));
+                get_adjustment_hints(acc, sema, cache, config, ast::Expr::from(it.clone()));
+                get_turbofish_placeholder_hints(acc, sema, cache, config, it);
+            },
+            ast::TryExpr(it) => {
+                get_question_mark_chain_hints(acc, sema, cache, config, it.clone());
+                get_question_mark_conversion_hints(acc, sema, cache, config, it);
+            },
+            ast::IndexExpr(it) => {
+                get_index_output_hints(acc, sema, cache, config, it);
+            },
+            ast::BinExpr(it) => {
+                get_binary_op_hints(acc, sema, cache, config, it);
+            },
+            ast::PrefixExpr(it) => {
+                get_deref_hints(acc, sema, cache, config, it);
+            },
+            ast::ArgList(it) => {
+                get_arg_list_type_hints(acc, sema, cache, config, it);
+            },
+            ast::Literal(it) => {
+                get_literal_type_hints(acc, sema, cache, config, it);
+            },
+            ast::IdentPat(it) => {
+                get_surprise_inference_hints(acc, sema, config, &it);
+                get_bind_pat_hints(acc, sema, cache, config, it);
+            },
+            ast::ClosureExpr(it) => {
+                get_closure_capture_hints(acc, sema, config, it);
+            },
+            _ => (),
+        }
+    }
+}
+
+/// Whether `name` contains `filter`, or matches it as a whole when it has `*`
+/// wildcards.
+fn matches_function_filter(filter: &str, name: &str) -> bool {
+    if !filter.contains('*') {
+        return name.contains(filter);
+    }
+    let mut segments = filter.split('*');
+    let first = segments.next().unwrap_or_default();
+    let mut rest = match name.strip_prefix(first) {
+        Some(it) => it,
+        None => return false,
+    };
+    let last = segments.next_back().unwrap_or_default();
+    for segment in segments {
+        rest = match rest.find(segment) {
+            Some(idx) => &rest[idx + segment.len()..],
+            None => return false,
+        };
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+/// Whether `node` is the receiver of a method call or field access, that is,
+/// an inner link of a (possibly multiline) chain.
+fn is_chain_receiver(node: &SyntaxNode) -> bool {
+    let parent = match node.parent() {
+        Some(it) => it,
+        None => return false,
+    };
+    let receiver = match_ast! {
+        match parent {
+            ast::MethodCallExpr(it) => it.receiver(),
+            ast::FieldExpr(it) => it.expr(),
+            _ => None,
+        }
+    };
+    matches!(receiver, Some(receiver) if receiver.syntax() == node)
+}
+
+/// The `.` continuing a chain after `node` on the next line.
+fn chain_continuation_dot(node: &SyntaxNode) -> Option<SyntaxToken> {
+    let mut tokens = node
+        .siblings_with_tokens(Direction::Next)
+        .filter_map(NodeOrToken::into_token)
+        .filter(|t| match t.kind() {
+            SyntaxKind::WHITESPACE if !t.text().contains('\n') => false,
+            SyntaxKind::COMMENT => false,
+            _ => true,
+        });
+
+    // Chaining can be defined as an expression whose next sibling tokens are newline and dot
+    // Ignoring extra whitespace and comments
+    let next = tokens.next()?;
+    let next_next = tokens.next()?;
+    if next.kind() == SyntaxKind::WHITESPACE && next_next.kind() == T![.] {
+        Some(next_next)
+    } else {
+        None
+    }
+}
+
+fn get_chaining_hints(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<RootDatabase>,
+    cache: &PassCache,
+    config: &InlayHintsConfig,
+    expr: ast::Expr,
+) -> Option<()> {
+    if !config.chaining_hints {
+        return None;
+    }
+    let _p = profile::span("get_chaining_hints");
+
+    if matches!(expr, ast::Expr::RecordExpr(_)) && !config.chaining_hints_on_record_literals {
+        return None;
+    }
+
+    if chain_continuation_dot(expr.syntax()).is_some() {
+        let ty = cache.type_of_expr(sema, &expr)?;
+        if ty.is_unknown() {
+            return None;
+        }
+        if matches!(expr, ast::Expr::PathExpr(_) | ast::Expr::RecordExpr(_)) {
+            if let Some(hir::Adt::Struct(st)) = ty.as_adt() {
+                if st.fields(sema.db).is_empty() {
+                    return None;
+                }
+            }
+        }
+        if config.chaining_hints_on_change_only {
+            let prev = match &expr {
+                ast::Expr::MethodCallExpr(it) => it.receiver(),
+                ast::Expr::FieldExpr(it) => it.expr(),
+                ast::Expr::TryExpr(it) => it.expr(),
+                ast::Expr::AwaitExpr(it) => it.expr(),
+                _ => None,
+            };
+            if let Some(prev) = prev {
+                if cache.type_of_expr(sema, &prev).as_ref() == Some(&ty) {
+                    return None;
+                }
+            }
+        }
+        acc.push(InlayHint {
+            range: expr.syntax().text_range(),
+            kind: InlayKind::ChainingHint,
+            label: type_label(sema, cache, config, &ty),
+            tooltip: type_tooltip(sema, config, &ty),
+            pad_left: false,
+            pad_right: false,
+            text_edit: None,
+            id: None,
+        });
+    }
+    Some(())
+}
+
+fn get_question_mark_chain_hints(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<RootDatabase>,
+    cache: &PassCache,
+    config: &InlayHintsConfig,
+    try_expr: ast::TryExpr,
+) -> Option<()> {
+    if !config.question_mark_chain_hints {
+        return None;
+    }
+    let _p = profile::span("get_question_mark_chain_hints");
+
+    // Find the outermost expression of the chain, then count the `?`s along
+    // its receivers.
+    let mut top = try_expr.syntax().clone();
+    while is_chain_receiver(&top)
+        || matches!(top.parent(), Some(parent) if ast::TryExpr::can_cast(parent.kind()))
+    {
+        top = top.parent()?;
+    }
+    let mut n_question_marks = 0;
+    let mut link = ast::Expr::cast(top);
+    while let Some(expr) = link {
+        link = match expr {
+            ast::Expr::TryExpr(it) => {
+                n_question_marks += 1;
+                it.expr()
+            }
+            ast::Expr::MethodCallExpr(it) => it.receiver(),
+            ast::Expr::FieldExpr(it) => it.expr(),
+            _ => None,
+        };
+    }
+    if n_question_marks < 2 {
+        return None;
+    }
+
+    let range = try_expr.syntax().text_range();
+    // A multiline chain may already have a chaining hint here.
+    if acc.iter().any(|hint| hint.range == range && hint.kind == InlayKind::ChainingHint) {
+        return None;
+    }
+    let ty = cache.type_of_expr(sema, &ast::Expr::from(try_expr))?;
+    if ty.is_unknown() {
+        return None;
+    }
+    acc.push(InlayHint {
+        range,
+        kind: InlayKind::ChainingHint,
+        label: type_label(sema, cache, config, &ty),
+        tooltip: type_tooltip(sema, config, &ty),
+        pad_left: false,
+        pad_right: false,
+        text_edit: None,
+        id: None,
+    });
+    Some(())
+}
+
+fn get_question_mark_conversion_hints(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<RootDatabase>,
+    cache: &PassCache,
+    config: &InlayHintsConfig,
+    try_expr: ast::TryExpr,
+) -> Option<()> {
+    if !config.question_mark_conversion_hints {
+        return None;
+    }
+    let _p = profile::span("get_question_mark_conversion_hints");
+
+    // `?` returns from the innermost function or closure.
+    let func = try_expr.syntax().ancestors().find_map(|node| {
+        match_ast! {
+            match node {
+                ast::Fn(it) => Some(Some(it)),
+                ast::ClosureExpr(_it) => Some(None),
+                _ => None,
+            }
+        }
+    })??;
+    let func = sema.to_def(&func)?;
+    let db = sema.db;
+    let try_trait = FamousDefs(sema, func.module(db).krate()).core_ops_Try()?;
+    let error_alias = try_trait.items(db).into_iter().find_map(|item| match item {
+        hir::AssocItem::TypeAlias(alias) if alias.name(db).to_string() == "Error" => Some(alias),
+        _ => None,
+    })?;
+
+    let operand_ty = cache.type_of_expr(sema, &try_expr.expr()?)?;
+    let from = operand_ty.normalize_trait_assoc_type(db, try_trait, &[], error_alias)?;
+    let to = func.ret_type(db).normalize_trait_assoc_type(db, try_trait, &[], error_alias)?;
+    if from.is_unknown() || to.is_unknown() || from == to {
+        return None;
+    }
+
+    let label = if config.lazy_labels {
+        InlayHintLabel::default()
+    } else {
+        let mut label = InlayHintLabel::from("as ");
+        label.append(type_label(sema, cache, config, &to));
+        label
+    };
+    acc.push(InlayHint {
+        range: try_expr.question_mark_token()?.text_range(),
+        kind: InlayKind::AdjustmentHint,
+        label,
+        tooltip: type_tooltip(sema, config, &to),
+        pad_left: false,
+        pad_right: false,
+        text_edit: None,
+        id: None,
+    });
+    Some(())
+}
+
+fn get_index_output_hints(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<RootDatabase>,
+    cache: &PassCache,
+    config: &InlayHintsConfig,
+    index_expr: ast::IndexExpr,
+) -> Option<()> {
+    if !config.index_output_hints {
+        return None;
+    }
+    let _p = profile::span("get_index_output_hints");
+    // `let value = map[key];` already gets a type hint on `value`.
+    let parent_let = index_expr.syntax().parent().and_then(ast::LetStmt::cast);
+    if let (true, Some(let_stmt)) = (config.type_hints, parent_let) {
+        if let_stmt.ty().is_none() && matches!(let_stmt.pat(), Some(ast::Pat::IdentPat(_))) {
+            return None;
+        }
+    }
+
+    // Inference resolves the `Index` impl the way rustc does, autoderefing
+    // the base and unsizing arrays to slices, and types the expression with
+    // its `Output`.
+    let output = cache.type_of_expr(sema, &index_expr.clone().into())?;
+    if output.is_unknown() {
+        return None;
+    }
+
+    acc.push(InlayHint {
+        range: index_expr.syntax().text_range(),
+        kind: InlayKind::TypeHint,
+        label: type_label(sema, cache, config, &output),
+        tooltip: type_tooltip(sema, config, &output),
+        pad_left: false,
+        pad_right: false,
+        text_edit: None,
+        id: None,
+    });
+    Some(())
+}
+
+fn get_binary_op_hints(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<RootDatabase>,
+    cache: &PassCache,
+    config: &InlayHintsConfig,
+    bin_expr: ast::BinExpr,
+) -> Option<()> {
+    if !config.operator_hints {
+        return None;
+    }
+    let _p = profile::span("get_binary_op_hints");
+    let lhs_ty = cache.type_of_expr(sema, &bin_expr.lhs()?)?;
+    let rhs_ty = cache.type_of_expr(sema, &bin_expr.rhs()?)?;
+
+    let db = sema.db;
+    let krate = sema.scope(bin_expr.syntax()).module()?.krate();
+    let op_trait = FamousDefs(sema, krate).core_ops_binary_op(bin_expr.op_kind()?)?;
+    let output_alias = op_trait.items(db).into_iter().find_map(|item| match item {
+        hir::AssocItem::TypeAlias(alias) if alias.name(db).to_string() == "Output" => Some(alias),
+        _ => None,
+    })?;
+    let output = lhs_ty.normalize_trait_assoc_type(
+        db,
+        op_trait,
+        std::slice::from_ref(&rhs_ty),
+        output_alias,
+    )?;
+    // Without a matching impl, the `Output` projection is left as is.
+    if output.is_unknown() || output.as_associated_type_parent_trait(db).is_some() {
+        return None;
+    }
+    // Like for the builtin numeric operators, a result of the operands' type
+    // is no surprise.
+    if output == lhs_ty && output == rhs_ty {
+        return None;
+    }
+
+    acc.push(InlayHint {
+        range: bin_expr.syntax().text_range(),
+        kind: InlayKind::TypeHint,
+        label: type_label(sema, cache, config, &output),
+        tooltip: type_tooltip(sema, config, &output),
+        pad_left: false,
+        pad_right: false,
+        text_edit: None,
+        id: None,
+    });
+    Some(())
+}
+
+fn get_deref_hints(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<RootDatabase>,
+    cache: &PassCache,
+    config: &InlayHintsConfig,
+    prefix_expr: ast::PrefixExpr,
+) -> Option<()> {
+    if !config.deref_hints || prefix_expr.op_kind()? != ast::PrefixOp::Deref {
+        return None;
+    }
+    let _p = profile::span("get_deref_hints");
+    // `let value = *boxed;` already gets a type hint on `value`.
+    let parent_let = prefix_expr.syntax().parent().and_then(ast::LetStmt::cast);
+    if let (true, Some(let_stmt)) = (config.type_hints, parent_let) {
+        if let_stmt.ty().is_none() && matches!(let_stmt.pat(), Some(ast::Pat::IdentPat(_))) {
+            return None;
+        }
+    }
+
+    // Inference derefs references and raw pointers natively, and anything
+    // else to its `Deref::Target`.
+    let target = cache.type_of_expr(sema, &prefix_expr.clone().into())?;
+    if target.is_unknown() {
+        return None;
+    }
+
+    acc.push(InlayHint {
+        range: prefix_expr.syntax().text_range(),
+        kind: InlayKind::TypeHint,
+        label: type_label(sema, cache, config, &target),
+        tooltip: type_tooltip(sema, config, &target),
+        pad_left: false,
+        pad_right: false,
+        text_edit: None,
+        id: None,
+    });
+    Some(())
+}
+
+/// Shows the types of the arguments of a call as a tuple, like `(i32, &str)`.
+fn get_arg_list_type_hints(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<RootDatabase>,
+    cache: &PassCache,
+    config: &InlayHintsConfig,
+    arg_list: ast::ArgList,
+) -> Option<()> {
+    if !config.arg_list_type_hints {
+        return None;
+    }
+    let _p = profile::span("get_arg_list_type_hints");
+    let types = arg_list
+        .args()
+        .map(|arg| cache.type_of_expr(sema, &arg).filter(|ty| !ty.is_unknown()))
+        .collect::<Option<Vec<_>>>()?;
+    if types.is_empty() {
+        return None;
+    }
+
+    let label = if config.lazy_labels {
+        InlayHintLabel::default()
+    } else {
+        let mut label = InlayHintLabel::from("(");
+        for (idx, ty) in types.iter().enumerate() {
+            if idx > 0 {
+                label.push_str(", ");
+            }
+            label.append(type_label(sema, cache, config, ty));
+        }
+        label.push_str(if types.len() == 1 { ",)" } else { ")" });
+        label
+    };
+    acc.push(InlayHint {
+        range: arg_list.syntax().text_range(),
+        kind: InlayKind::TypeHint,
+        label,
+        tooltip: None,
+        pad_left: false,
+        pad_right: false,
+        text_edit: None,
+        id: None,
+    });
+    Some(())
+}
+
+/// Shows the type of generic enum variants used as values, like `None` or
+/// `Ok(1)`, when neither a turbofish nor a `let` binding spells it out.
+fn get_inferred_constructor_hints(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<RootDatabase>,
+    cache: &PassCache,
+    config: &InlayHintsConfig,
+    expr: ast::Expr,
+) -> Option<()> {
+    if !config.inferred_constructor_type_hints {
+        return None;
+    }
+    let _p = profile::span("get_inferred_constructor_hints");
+    let path = match &expr {
+        ast::Expr::CallExpr(it) => match it.expr()? {
+            ast::Expr::PathExpr(callee) => callee.path()?,
+            _ => return None,
+        },
+        // The callees of calls are hinted along with their call.
+        ast::Expr::PathExpr(it) if !is_callee(it) => it.path()?,
+        _ => return None,
+    };
+    let variant = match sema.resolve_path(&path)? {
+        hir::PathResolution::Def(hir::ModuleDef::EnumVariant(it)) => it,
+        _ => return None,
+    };
+    if hir::GenericDef::from(variant.parent_enum(sema.db)).params(sema.db).is_empty() {
+        return None;
+    }
+    let spells_generic_args = std::iter::successors(Some(path), |it| it.qualifier())
+        .any(|it| it.segment().and_then(|it| it.generic_arg_list()).is_some());
+    let is_let_initializer =
+        expr.syntax().parent().and_then(ast::LetStmt::cast).and_then(|it| it.initializer())
+            == Some(expr.clone());
+    if spells_generic_args || is_let_initializer {
+        return None;
+    }
+    let ty = cache.type_of_expr(sema, &expr)?;
+    if ty.contains_unknown() {
+        return None;
+    }
+
+    acc.push(InlayHint {
+        range: expr.syntax().text_range(),
+        kind: InlayKind::TypeHint,
+        label: type_label(sema, cache, config, &ty),
+        tooltip: type_tooltip(sema, config, &ty),
+        pad_left: false,
+        pad_right: false,
+        text_edit: None,
+        id: None,
+    });
+    Some(())
+}
+
+fn is_callee(path_expr: &ast::PathExpr) -> bool {
+    let call = path_expr.syntax().parent().and_then(ast::CallExpr::cast);
+    matches!(call.and_then(|it| it.expr()), Some(ast::Expr::PathExpr(it)) if &it == path_expr)
+}
+
+fn get_literal_type_hints(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<RootDatabase>,
+    cache: &PassCache,
+    config: &InlayHintsConfig,
+    literal: ast::Literal,
+) -> Option<()> {
+    if !config.literal_type_hints {
+        return None;
+    }
+    let _p = profile::span("get_literal_type_hints");
+    let expr = ast::Expr::Literal(literal);
+    let default = syntactic_default_type(&expr)?;
+    let ty = cache.type_of_expr(sema, &expr)?;
+    if ty.is_unknown() || ty.display(sema.db).to_string() == default {
+        return None;
+    }
+
+    acc.push(InlayHint {
+        range: expr.syntax().text_range(),
+        kind: InlayKind::TypeHint,
+        label: type_label(sema, cache, config, &ty),
+        tooltip: type_tooltip(sema, config, &ty),
+        pad_left: false,
+        pad_right: false,
+        text_edit: None,
+        id: None,
+    });
+    Some(())
+}
+
+/// Fills in the `_`s of a turbofish like `collect::<Vec<_>>()`, when the
+/// method returns the type given by the turbofish.
+fn get_turbofish_placeholder_hints(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<RootDatabase>,
+    cache: &PassCache,
+    config: &InlayHintsConfig,
+    call: ast::MethodCallExpr,
+) -> Option<()> {
+    if !config.type_hints {
+        return None;
+    }
+    let _p = profile::span("get_turbofish_placeholder_hints");
+    let type_args = call
+        .generic_arg_list()?
+        .generic_args()
+        .filter_map(|arg| match arg {
+            ast::GenericArg::TypeArg(it) => it.ty(),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    if !type_args
+        .iter()
+        .any(|it| it.syntax().descendants().any(|it| it.kind() == SyntaxKind::INFER_TYPE))
+    {
+        return None;
+    }
+
+    // Find the type parameter the method returns, like the `B` of
+    // `fn collect<B: FromIterator<Self::Item>>(self) -> B`.
+    let func = sema.resolve_method_call(&call)?.source(sema.db).value;
+    let ret_type = match func.ret_type()?.ty()? {
+        ast::Type::PathType(it) => it.path()?,
+        _ => return None,
+    };
+    if ret_type.qualifier().is_some() {
+        return None;
+    }
+    let ret_type = ret_type.segment()?.name_ref()?;
+    let idx = func
+        .generic_param_list()?
+        .type_params()
+        .position(|param| matches!(param.name(), Some(name) if name.text() == ret_type.text()))?;
+    let type_arg = type_args.get(idx)?;
+    let ty = cache.type_of_expr(sema, &ast::Expr::from(call))?;
+
+    let mut placeholders = Vec::new();
+    collect_placeholder_types(sema.db, type_arg.clone(), ty, &mut placeholders);
+    for (placeholder, ty) in placeholders {
+        if ty.is_unknown() {
+            continue;
+        }
+        acc.push(InlayHint {
+            range: placeholder.syntax().text_range(),
+            kind: InlayKind::TypeHint,
+            label: type_label(sema, cache, config, &ty),
+            tooltip: type_tooltip(sema, config, &ty),
+            pad_left: false,
+            pad_right: false,
+            text_edit: None,
+            id: None,
+        });
+    }
+    Some(())
+}
+
+/// Matches the `_`s in `ty_syntax` with the corresponding parts of `ty`.
+fn collect_placeholder_types(
+    db: &RootDatabase,
+    ty_syntax: ast::Type,
+    ty: hir::Type,
+    acc: &mut Vec<(ast::InferType, hir::Type)>,
+) {
+    let children = match ty_syntax {
+        ast::Type::InferType(it) => {
+            acc.push((it, ty));
+            return;
+        }
+        ast::Type::RefType(it) => it.ty().into_iter().zip(ty.remove_ref()).collect(),
+        ast::Type::TupleType(it) => it.fields().zip(ty.tuple_fields(db)).collect(),
+        ast::Type::PathType(it) if ty.as_adt().is_some() => {
+            let args = it
+                .path()
+                .and_then(|path| path.segment())
+                .and_then(|segment| segment.generic_arg_list())
+                .into_iter()
+                .flat_map(|it| it.generic_args())
+                .filter_map(|arg| match arg {
+                    ast::GenericArg::TypeArg(it) => it.ty(),
+                    _ => None,
+                });
+            args.zip(ty.type_parameters()).collect()
+        }
+        _ => Vec::new(),
+    };
+    for (ty_syntax, ty) in children {
+        collect_placeholder_types(db, ty_syntax, ty, acc);
+    }
+}
+
+fn get_param_name_hints(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<RootDatabase>,
+    cache: &PassCache,
+    config: &InlayHintsConfig,
+    expr: ast::Expr,
+) -> Option<()> {
+    if !config.parameter_hints {
+        return None;
+    }
+    let _p = profile::span("get_param_name_hints");
+
+    let args = match &expr {
+        ast::Expr::CallExpr(expr) => expr.arg_list()?.args(),
+        ast::Expr::MethodCallExpr(expr) => expr.arg_list()?.args(),
+        _ => return None,
+    };
+
+    let callable = get_callable(sema, cache, &expr)?;
+    if let ast::Expr::MethodCallExpr(method_call) = &expr {
+        if config.self_param_hints && !config.self_param_hints_only_when_ufcs {
+            get_receiver_hint(acc, sema, cache, config, &callable, method_call);
+        }
+    }
+    let tuple_constructor = config.tuple_constructor_hints
+        && matches!(
+            callable.kind(),
+            hir::CallableKind::TupleStruct(_) | hir::CallableKind::TupleEnumVariant(_)
+        );
+    let hints = callable
+        .params(sema.db)
+        .into_iter()
+        .zip(args)
+        .enumerate()
+        .filter_map(|(idx, ((param, ty), arg))| {
+            // Filtering happens after zipping, so skipping `self` keeps the
+            // remaining hints aligned with their arguments.
+            if arg.syntax().text_range().is_empty() {
+                // Recovered from a half-typed call, with nothing to point at.
+                return None;
+            }
+            let param_name = match param {
+                Some(Either::Left(_)) if !config.self_param_hints => return None,
+                Some(Either::Left(self_param)) => self_param_label(&self_param),
+                Some(Either::Right(ast::Pat::IdentPat(it))) => it.name()?.as_name().to_string(),
+                None if tuple_constructor => format!(".{}", idx),
+                Some(Either::Right(_)) | None => return None,
+            };
+            Some((param_name, ty, arg))
+        })
+        .filter(|(param_name, _ty, arg)| {
+            should_show_param_name_hint(sema, cache, &callable, &param_name, &arg)
+        })
+        .map(|(param_name, ty, arg)| InlayHint {
+            range: arg.syntax().text_range(),
+            kind: InlayKind::ParameterHint,
+            tooltip: if config.hint_tooltips && !config.lazy_labels {
+                Some(Markup::fenced_block(&format!("{}: {}", param_name, ty.display(sema.db))))
+            } else {
+                None
+            },
+            label: param_name.into(),
+            pad_left: false,
+            pad_right: false,
+            text_edit: None,
+            id: None,
+        });
+
+    acc.extend(hints);
+    Some(())
+}
+
+/// Hints the receiver of a method call with the `self` parameter it is
+/// passed as, which `Callable::params` leaves out for method call syntax.
+fn get_receiver_hint(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<RootDatabase>,
+    cache: &PassCache,
+    config: &InlayHintsConfig,
+    callable: &hir::Callable,
+    method_call: &ast::MethodCallExpr,
+) -> Option<()> {
+    let receiver = method_call.receiver()?;
+    let param_name = self_param_label(&callable.receiver_param(sema.db)?);
+    if !should_show_param_name_hint(sema, cache, callable, &param_name, &receiver) {
+        return None;
+    }
+    let tooltip = if config.hint_tooltips && !config.lazy_labels {
+        let ty = cache.type_of_expr(sema, &receiver)?;
+        Some(Markup::fenced_block(&format!("{}: {}", param_name, ty.display(sema.db))))
+    } else {
+        None
+    };
+    acc.push(InlayHint {
+        range: receiver.syntax().text_range(),
+        kind: InlayKind::ParameterHint,
+        tooltip,
+        label: param_name.into(),
+        pad_left: false,
+        pad_right: false,
+        text_edit: None,
+        id: None,
+    });
+    Some(())
+}
+
+/// Flags the arguments that are variables named after another parameter of
+/// the call, which are likely swapped with the argument passed to it.
+fn get_arg_order_mismatch_hints(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<RootDatabase>,
+    cache: &PassCache,
+    config: &InlayHintsConfig,
+    expr: ast::Expr,
+) -> Option<()> {
+    if !config.arg_order_mismatch_hints {
+        return None;
+    }
+    let _p = profile::span("get_arg_order_mismatch_hints");
+
+    let args = match &expr {
+        ast::Expr::CallExpr(expr) => expr.arg_list()?.args(),
+        ast::Expr::MethodCallExpr(expr) => expr.arg_list()?.args(),
+        _ => return None,
+    };
+
+    let callable = get_callable(sema, cache, &expr)?;
+    let (param_names, args): (Vec<_>, Vec<_>) = callable
+        .params(sema.db)
+        .into_iter()
+        .zip(args)
+        .map(|((param, _ty), arg)| {
+            let param_name = match param {
+                Some(Either::Right(ast::Pat::IdentPat(it))) => {
+                    it.name().map(|name| name.as_name().to_string())
+                }
+                _ => None,
+            };
+            (param_name, arg)
+        })
+        .unzip();
+
+    for (param_name, arg) in param_names.iter().zip(&args) {
+        let (param_name, arg_name) = match (param_name, local_name(arg)) {
+            (Some(param_name), Some(arg_name)) => (param_name, arg_name),
+            _ => continue,
+        };
+        if *param_name == arg_name
+            || !param_names.iter().any(|it| it.as_deref() == Some(arg_name.as_str()))
+        {
+            continue;
+        }
+        acc.push(InlayHint {
+            range: arg.syntax().text_range(),
+            kind: InlayKind::ArgOrderMismatchHint,
+            label: format!("{}, not {}", param_name, arg_name).into(),
+            tooltip: None,
+            pad_left: false,
+            pad_right: false,
+            text_edit: None,
+            id: None,
+        });
+    }
+    Some(())
+}
+
+/// The name of the variable `expr` reads, possibly through a reference.
+fn local_name(expr: &ast::Expr) -> Option<String> {
+    match expr {
+        ast::Expr::PathExpr(it) => {
+            let path = it.path()?;
+            if path.qualifier().is_some() {
+                return None;
+            }
+            Some(path.segment()?.name_ref()?.to_string())
+        }
+        ast::Expr::RefExpr(it) => local_name(&it.expr()?),
+        _ => None,
+    }
+}
+
+/// Renders a `self` parameter, including arbitrary self types like
+/// `self: Pin<&mut Self>`, independently of how it's formatted in the source.
+fn self_param_label(self_param: &ast::SelfParam) -> String {
+    let mut res = String::new();
+    if let Some(ty) = self_param.ty() {
+        if self_param.mut_token().is_some() {
+            res.push_str("mut ");
+        }
+        format_to!(res, "self: {}", ty);
+        return res;
+    }
+    if self_param.amp_token().is_some() {
+        res.push('&');
+        if let Some(lifetime) = self_param.lifetime_token() {
+            format_to!(res, "{} ", lifetime);
+        }
+    }
+    if self_param.mut_token().is_some() {
+        res.push_str("mut ");
+    }
+    res.push_str("self");
+    res
+}
+
+/// Shows the borrow the receiver of a UFCS call like `Trait::method(x)` needs
+/// when `self` expects `&T` or `&mut T` but `x` is a `T`, and the target of
+/// deref coercions, as in `as &str` for a `&String` argument. Only receivers
+/// are ever autoref'd, so other arguments never get a borrow hint.
+fn get_adjustment_hints(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<RootDatabase>,
+    cache: &PassCache,
+    config: &InlayHintsConfig,
+    expr: ast::Expr,
+) -> Option<()> {
+    if !config.adjustment_hints {
+        return None;
+    }
+    let _p = profile::span("get_adjustment_hints");
+
+    let args = match &expr {
+        ast::Expr::CallExpr(expr) => expr.arg_list()?.args(),
+        ast::Expr::MethodCallExpr(expr) => expr.arg_list()?.args(),
+        _ => return None,
+    };
+
+    let callable = get_callable(sema, cache, &expr)?;
+    // When a trait method is called through the trait, as in
+    // `Trait::method(x)`, `Self` is inferred from the receiver argument. If
+    // that argument is missing its borrow, `Self` stays unknown, so check
+    // whether the argument implements the trait instead.
+    let self_trait = match callable.kind() {
+        hir::CallableKind::Function(it) => {
+            match it.as_assoc_item(sema.db).map(|it| it.container(sema.db)) {
+                Some(hir::AssocItemContainer::Trait(it)) => Some(it),
+                _ => None,
+            }
+        }
+        _ => None,
+    };
+    // Unlike for parameter hints, `self` is not skipped here: for a method
+    // call the receiver is not among `args`, and for a UFCS call it is the
+    // only argument that gets borrowed.
+    let hints =
+        callable.params(sema.db).into_iter().zip(args).filter_map(|((param, param_ty), arg)| {
+            let target = param_ty.remove_ref()?;
+            let arg_ty = cache.type_of_expr(sema, &arg)?;
+            if is_deref_coercion(sema.db, &arg_ty, &param_ty) {
+                let label = if config.lazy_labels {
+                    InlayHintLabel::default()
+                } else {
+                    let mut label = InlayHintLabel::from("as ");
+                    label.append(type_label(sema, cache, config, &param_ty));
+                    label
+                };
+                return Some(InlayHint {
+                    range: arg.syntax().text_range(),
+                    kind: InlayKind::AdjustmentHint,
+                    label,
+                    tooltip: type_tooltip(sema, config, &param_ty),
+                    pad_left: false,
+                    pad_right: false,
+                    text_edit: None,
+                    id: None,
+                });
+            }
+            if arg_ty.remove_ref().is_some() {
+                return None;
+            }
+            let borrows = match (param, self_trait) {
+                (Some(Either::Left(_)), Some(trait_)) if target.is_unknown() => {
+                    arg_ty.impls_trait(sema.db, trait_, &[])
+                }
+                (Some(Either::Left(_)), _) => arg_ty == target,
+                _ => false,
+            };
+            if !borrows {
+                return None;
+            }
+            let label = if param_ty.is_mutable_reference() { "&mut" } else { "&" };
+            Some(InlayHint {
+                range: arg.syntax().text_range(),
+                kind: InlayKind::AdjustmentHint,
+                label: label.into(),
+                tooltip: None,
+                pad_left: false,
+                pad_right: false,
+                text_edit: None,
+                id: None,
+            })
+        });
+
+    acc.extend(hints);
+    Some(())
+}
+
+/// Whether passing `arg_ty` where `param_ty` is expected goes through `Deref`,
+/// like `&String` to `&str`: both are references, and the parameter's pointee
+/// is reached by dereferencing the argument's one at least once.
+fn is_deref_coercion(db: &RootDatabase, arg_ty: &hir::Type, param_ty: &hir::Type) -> bool {
+    let (source, target) = match (arg_ty.remove_ref(), param_ty.remove_ref()) {
+        (Some(source), Some(target)) => (source, target),
+        _ => return false,
+    };
+    if source.is_unknown() || target.is_unknown() || source == target {
+        return false;
+    }
+    if param_ty.is_mutable_reference() && !arg_ty.is_mutable_reference() {
+        return false;
+    }
+    let mut derefs = source.autoderef(db).skip(1);
+    derefs.any(|it| it == target)
+}
+
+/// How a closure uses a binding of its environment. Later variants are
+/// stronger, and the strongest use of a binding decides how it's captured.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum CaptureKind {
+    Ref,
+    MutRef,
+    /// Taken by value by a `move` closure, without being moved out of it.
+    Move,
+    /// Moved out of the closure body, which makes the closure `FnOnce`.
+    Consume,
+}
+
+/// Lists the bindings of the enclosing function a closure uses, with how it
+/// captures them, before its parameter list.
+fn get_closure_capture_hints(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<RootDatabase>,
+    config: &InlayHintsConfig,
+    closure: ast::ClosureExpr,
+) -> Option<()> {
+    if !config.closure_capture_hints {
+        return None;
+    }
+    let _p = profile::span("get_closure_capture_hints");
+    let param_list = closure.param_list()?;
+    let body = closure.body()?;
+    let db = sema.db;
+
+    let closure_range = closure.syntax().text_range();
+    let mut captures: Vec<(hir::Local, CaptureKind)> = Vec::new();
+    for path_expr in body.syntax().descendants().filter_map(ast::PathExpr::cast) {
+        let local = match path_expr.path().and_then(|path| sema.resolve_path(&path)) {
+            Some(hir::PathResolution::Local(it)) => it,
+            _ => continue,
+        };
+        let def_range = match local.source(db).value {
+            Either::Left(pat) => pat.syntax().text_range(),
+            Either::Right(self_param) => self_param.syntax().text_range(),
+        };
+        // Parameters and bindings of the closure itself aren't captured.
+        if closure_range.contains_range(def_range) {
+            continue;
+        }
+        let mut kind = capture_kind(sema, &path_expr, &local.ty(db));
+        if closure.move_token().is_some() {
+            kind = kind.max(CaptureKind::Move);
+        }
+        match captures.iter_mut().find(|(it, _)| *it == local) {
+            Some((_, prev)) => *prev = kind.max(*prev),
+            None => captures.push((local, kind)),
+        }
+    }
+
+    for (local, kind) in captures {
+        let name = local.name(db)?;
+        let label = match kind {
+            CaptureKind::Ref => format!("&{}", name),
+            CaptureKind::MutRef => format!("&mut {}", name),
+            CaptureKind::Move => name.to_string(),
+            CaptureKind::Consume => format!("move {}", name),
+        };
+        let tooltip = if config.hint_tooltips && !config.lazy_labels {
+            Some(Markup::fenced_block(&format!("{}: {}", name, local.ty(db).display(db))))
+        } else {
+            None
+        };
+        acc.push(InlayHint {
+            range: param_list.syntax().text_range(),
+            kind: InlayKind::ClosureCaptureHint,
+            label: label.into(),
+            tooltip,
+            pad_left: false,
+            pad_right: false,
+            text_edit: None,
+            id: None,
+        });
+    }
+    Some(())
+}
+
+/// How the use of a binding at `path_expr`, of type `ty`, needs it captured.
+fn capture_kind(
+    sema: &Semantics<RootDatabase>,
+    path_expr: &ast::PathExpr,
+    ty: &hir::Type,
+) -> CaptureKind {
+    let by_value = || if ty.is_copy(sema.db) { CaptureKind::Ref } else { CaptureKind::Consume };
+    let mut node = path_expr.syntax().clone();
+    while let Some(parent) = node.parent().filter(|it| ast::ParenExpr::can_cast(it.kind())) {
+        node = parent;
+    }
+    let parent = match node.parent() {
+        Some(it) => it,
+        None => return CaptureKind::Ref,
+    };
+    match_ast! {
+        match parent {
+            ast::RefExpr(it) => {
+                if it.mut_token().is_some() { CaptureKind::MutRef } else { CaptureKind::Ref }
+            },
+            ast::BinExpr(it) => {
+                let is_lhs = matches!(it.lhs(), Some(lhs) if lhs.syntax() == &node);
+                match it.op_kind() {
+                    Some(op) if op.is_assignment() && is_lhs => CaptureKind::MutRef,
+                    // Comparisons take their operands by reference.
+                    Some(ast::BinOp::EqualityTest)
+                    | Some(ast::BinOp::NegatedEqualityTest)
+                    | Some(ast::BinOp::LesserEqualTest)
+                    | Some(ast::BinOp::GreaterEqualTest)
+                    | Some(ast::BinOp::LesserTest)
+                    | Some(ast::BinOp::GreaterTest) => CaptureKind::Ref,
+                    _ => by_value(),
+                }
+            },
+            // Arguments have an `ArgList` as their parent, so this is the receiver.
+            ast::MethodCallExpr(it) => {
+                let self_param = sema.resolve_method_call(&it).and_then(|it| it.self_param(sema.db));
+                match self_param.map(|it| it.access(sema.db)) {
+                    Some(hir::Access::Owned) => by_value(),
+                    Some(hir::Access::Exclusive) => CaptureKind::MutRef,
+                    Some(hir::Access::Shared) | None => CaptureKind::Ref,
+                }
+            },
+            ast::PrefixExpr(it) => {
+                if it.op_kind() != Some(ast::PrefixOp::Deref) {
+                    return by_value();
+                }
+                match it.syntax().parent().and_then(ast::BinExpr::cast) {
+                    Some(bin)
+                        if matches!(bin.op_kind(), Some(op) if op.is_assignment())
+                            && matches!(bin.lhs(), Some(lhs) if lhs.syntax() == it.syntax()) =>
+                    {
+                        CaptureKind::MutRef
+                    }
+                    _ => CaptureKind::Ref,
+                }
+            },
+            ast::LetStmt(it) => {
+                // `let _ = x;` doesn't move out of `x`.
+                if matches!(it.pat(), Some(ast::Pat::WildcardPat(_))) {
+                    CaptureKind::Ref
+                } else {
+                    by_value()
+                }
+            },
+            // Places that are only read from, or called.
+            ast::FieldExpr(_it) => CaptureKind::Ref,
+            ast::IndexExpr(_it) => CaptureKind::Ref,
+            ast::MatchExpr(_it) => CaptureKind::Ref,
+            ast::Condition(_it) => CaptureKind::Ref,
+            ast::CallExpr(_it) => CaptureKind::Ref,
+            _ => by_value(),
+        }
+    }
+}
+
+fn get_bind_pat_hints(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<RootDatabase>,
+    cache: &PassCache,
+    config: &InlayHintsConfig,
+    pat: ast::IdentPat,
+) -> Option<()> {
+    if !config.type_hints {
+        return None;
+    }
+    let _p = profile::span("get_bind_pat_hints");
+
+    let ty = sema.type_of_pat(&pat.clone().into())?;
+
+    if should_not_display_type_hint(sema, cache, &pat, &ty) {
+        return None;
+    }
+    if is_repeated_or_pat_binding(&pat) {
+        return None;
+    }
+    if config.hide_string_type_hints && is_string_type(sema, &ty) {
+        return None;
+    }
+    if config.closure_style == ClosureStyle::Hide && ty.is_closure() {
+        return None;
+    }
+    if config.hide_hints_for_obvious_constructors {
+        let initializer =
+            pat.syntax().parent().and_then(ast::LetStmt::cast).and_then(|it| it.initializer());
+        if let Some(initializer) = initializer {
+            if is_obvious_constructor(sema.db, &initializer, &ty) {
+                return None;
+            }
+        }
+    }
+    if config.hide_hints_when_type_in_rhs {
+        let let_stmt = pat.syntax().parent().and_then(ast::LetStmt::cast);
+        if let Some(let_stmt) = let_stmt {
+            if is_type_named_in_rhs(sema.db, &let_stmt, &ty) {
+                return None;
+            }
+        }
+    }
+    // Only the label shows the concrete type: it can't be written in an
+    // annotation, so the text edit keeps the opaque one.
+    let hidden_ty =
+        if config.expand_impl_trait_hints { ty.impl_trait_hidden_type(sema.db) } else { None };
+    let label_ty = hidden_ty.as_ref().unwrap_or(&ty);
+    let mut label = match &hidden_ty {
+        Some(hidden_ty) => concrete_type_label(sema, config, hidden_ty),
+        None => tuple_struct_doc_label(sema, config, &ty)
+            .unwrap_or_else(|| type_label(sema, cache, config, &ty)),
+    };
+    if config.show_lifetimes_in_hints && !config.lazy_labels {
+        let extended = extended_temporary_refs(&pat);
+        if extended > 0 {
+            label = with_block_lifetimes(label, extended);
+        }
+    }
+    let text_edit = if config.lazy_labels { None } else { type_annotation_edit(sema, &pat, &ty) };
+    // A binding with a subpattern, like `tail @ ..`, is hinted right after
+    // its name rather than after the subpattern.
+    let range = match pat.pat() {
+        Some(_) => pat.name()?.syntax().text_range(),
+        None => pat.syntax().text_range(),
+    };
+    acc.push(InlayHint {
+        range,
+        kind: InlayKind::TypeHint,
+        label,
+        tooltip: type_tooltip(sema, config, label_ty),
+        pad_left: false,
+        pad_right: false,
+        text_edit,
+        id: None,
+    });
+
+    Some(())
+}
+
+/// Whether `pat` binds a name already bound by an earlier alternative of an
+/// or-pattern, like the second `x` of `Foo(x) | Bar(x)`. Every alternative
+/// binds the same names with the same types, so the first one is enough.
+fn is_repeated_or_pat_binding(pat: &ast::IdentPat) -> bool {
+    let name = match pat.name() {
+        Some(it) => it.text().clone(),
+        None => return false,
+    };
+    let binds_name = |alternative: &ast::Pat| {
+        alternative
+            .syntax()
+            .descendants()
+            .filter_map(ast::IdentPat::cast)
+            .any(|it| it.name().map(|it| it.text().clone()).as_ref() == Some(&name))
+    };
+    pat.syntax().ancestors().filter_map(ast::OrPat::cast).any(|or_pat| {
+        or_pat
+            .pats()
+            .take_while(|it| !it.syntax().text_range().contains_range(pat.syntax().text_range()))
+            .any(|it| binds_name(&it))
+    })
+}
+
+/// Whether `ty` is `String`, `&str`, `&String` or `&mut String`.
+fn is_string_type(sema: &Semantics<RootDatabase>, ty: &hir::Type) -> bool {
+    let is_string = |ty: &hir::Type| match ty.as_adt() {
+        Some(hir::Adt::Struct(s)) => {
+            Some(s) == FamousDefs(sema, s.module(sema.db).krate()).alloc_string_String()
+        }
+        _ => false,
+    };
+    match ty.remove_ref() {
+        Some(inner) => (inner.is_str() && !ty.is_mutable_reference()) || is_string(&inner),
+        None => is_string(ty),
+    }
+}
+
+/// Inserts `: Type` after `pat`, when it is the whole pattern of a `let` or
+/// of a closure parameter. Bindings nested in tuple or struct patterns can't
+/// be annotated on their own, so those are skipped.
+fn type_annotation_edit(
+    sema: &Semantics<RootDatabase>,
+    pat: &ast::IdentPat,
+    ty: &hir::Type,
+) -> Option<TextEdit> {
+    let parent = pat.syntax().parent()?;
+    let annotated = match_ast! {
+        match parent {
+            ast::LetStmt(it) => it.ty().is_some(),
+            ast::Param(it) => it.ty().is_some(),
+            _ => return None,
+        }
+    };
+    if annotated || ty.contains_unknown() || ty.contains_impl_trait() || ty.is_closure() {
+        return None;
+    }
+    let module = sema.scope(pat.syntax()).module()?;
+    let ty = ty.display_source_code(sema.db, module.into()).ok()?;
+    Some(TextEdit::insert(pat.syntax().text_range().end(), format!(": {}", ty)))
+}
+
+/// The number of leading references in the initializer of `let pat = &...;`
+/// that point to a temporary, and so live until the end of the enclosing
+/// block instead of the end of the statement.
+fn extended_temporary_refs(pat: &ast::IdentPat) -> usize {
+    let let_stmt = match pat.syntax().parent().and_then(ast::LetStmt::cast) {
+        Some(it) => it,
+        None => return 0,
+    };
+    let mut depth = 0;
+    let mut expr = let_stmt.initializer();
+    while let Some(ast::Expr::RefExpr(it)) = expr {
+        depth += 1;
+        expr = it.expr();
+    }
+    match expr {
+        Some(expr) if !is_place_expr(&expr) => depth,
+        _ => 0,
+    }
+}
+
+fn is_place_expr(expr: &ast::Expr) -> bool {
+    match expr {
+        ast::Expr::PathExpr(_) | ast::Expr::IndexExpr(_) => true,
+        ast::Expr::PrefixExpr(it) => it.op_kind() == Some(ast::PrefixOp::Deref),
+        ast::Expr::FieldExpr(it) => matches!(it.expr(), Some(base) if is_place_expr(&base)),
+        ast::Expr::ParenExpr(it) => matches!(it.expr(), Some(inner) if is_place_expr(&inner)),
+        _ => false,
+    }
+}
+
+/// Rewrites the first `depth` references of a label, like `&&T`, into
+/// `&'block &'block T`.
+fn with_block_lifetimes(label: InlayHintLabel, depth: usize) -> InlayHintLabel {
+    match label.parts.first() {
+        Some(first) if first.goto.is_none() => (),
+        _ => return label,
+    }
+    let mut parts = label.parts.into_iter();
+    let first = parts.next().unwrap().text;
+    let mut rest = first.as_str();
+    let mut res = InlayHintLabel::default();
+    for _ in 0..depth {
+        rest = match rest.strip_prefix('&') {
+            Some(it) => it,
+            None => break,
+        };
+        match rest.strip_prefix("mut ") {
+            Some(it) => {
+                res.push_str("&'block mut ");
+                rest = it;
+            }
+            None => res.push_str("&'block "),
+        }
+    }
+    res.push_str(rest);
+    res.append(InlayHintLabel { parts: parts.collect() });
+    res
+}
+
+/// Renders a tuple struct as `Size(width: u32, height: u32)`, taking the
+/// names from the fields' doc comments. Undocumented positions show just the
+/// type, and structs without any documented field are left to `type_label`.
+fn tuple_struct_doc_label(
+    sema: &Semantics<RootDatabase>,
+    config: &InlayHintsConfig,
+    ty: &hir::Type,
+) -> Option<InlayHintLabel> {
+    if !config.tuple_struct_field_doc_hints || config.lazy_labels {
+        return None;
+    }
+    let db = sema.db;
+    let strukt = match ty.as_adt()? {
+        hir::Adt::Struct(it) if it.kind(db) == hir::StructKind::Tuple => it,
+        _ => return None,
+    };
+
+    let fields = ty.fields(db);
+    let names = fields
+        .iter()
+        .map(|(field, _)| {
+            let docs = field.docs(db)?;
+            let word = docs
+                .as_str()
+                .split_whitespace()
+                .next()?
+                .trim_matches(|c: char| !c.is_alphanumeric() && c != '_');
+            if word.is_empty() {
+                return None;
+            }
+            Some(to_lower_snake_case(word))
+        })
+        .collect::<Vec<_>>();
+    if names.iter().all(Option::is_none) {
+        return None;
+    }
+
+    let positions = fields
+        .iter()
+        .zip(names)
+        .map(|((_, field_ty), name)| {
+            let field_ty = render_type(db, config, field_ty, config.max_length);
+            match name {
+                Some(name) => format!("{}: {}", name, field_ty),
+                None => field_ty.to_string(),
+            }
+        })
+        .collect::<Vec<_>>();
+    Some(format!("{}({})", strukt.name(db), positions.join(", ")).into())
+}
+
+/// Flags `let` bindings whose initializer looks like it has one type, but was
+/// inferred to another one, like an integer literal that ended up as `usize`.
+fn get_surprise_inference_hints(
+    acc: &mut Vec<InlayHint>,
+    sema: &Semantics<RootDatabase>,
+    config: &InlayHintsConfig,
+    pat: &ast::IdentPat,
+) -> Option<()> {
+    if !config.surprise_inference_hints {
+        return None;
+    }
+    let _p = profile::span("get_surprise_inference_hints");
+
+    let let_stmt = pat.syntax().parent().and_then(ast::LetStmt::cast)?;
+    if let_stmt.ty().is_some() {
+        return None;
+    }
+    let expected = syntactic_default_type(&let_stmt.initializer()?)?;
+    let ty = sema.type_of_pat(&pat.clone().into())?;
+    if ty.is_unknown() {
+        return None;
+    }
+    let actual = ty.display(sema.db).to_string();
+    if actual == expected {
+        return None;
+    }
+    acc.push(InlayHint {
+        range: pat.syntax().text_range(),
+        kind: InlayKind::SurpriseInferenceHint,
+        label: if config.lazy_labels {
+            InlayHintLabel::default()
+        } else {
+            format!("{}, not {}", actual, expected).into()
+        },
+        tooltip: None,
+        pad_left: false,
+        pad_right: false,
+        text_edit: None,
+        id: None,
+    });
+
+    Some(())
+}
+
+/// The type a reader would assume for `expr` without looking at how it is used.
+fn syntactic_default_type(expr: &ast::Expr) -> Option<&'static str> {
+    match expr {
+        ast::Expr::Literal(lit) => match lit.kind() {
+            ast::LiteralKind::IntNumber { suffix: None } => Some("i32"),
+            ast::LiteralKind::FloatNumber { suffix: None } => Some("f64"),
+            _ => None,
+        },
+        ast::Expr::ParenExpr(it) => syntactic_default_type(&it.expr()?),
+        ast::Expr::PrefixExpr(it) if it.op_kind() == Some(ast::PrefixOp::Neg) => {
+            syntactic_default_type(&it.expr()?)
+        }
+        _ => None,
+    }
+}
+
+fn type_label(
+    sema: &Semantics<RootDatabase>,
+    cache: &PassCache,
+    config: &InlayHintsConfig,
+    ty: &hir::Type,
+) -> InlayHintLabel {
+    if config.lazy_labels {
+        return InlayHintLabel::default();
+    }
+    hint_assoc_shorten(sema, cache, config, ty, ASSOC_SHORTENINGS)
+        .or_else(|| hint_result_shorten(sema, config, ty))
+        .unwrap_or_else(|| {
+            let text = render_type(sema.db, config, ty, config.max_length);
+            linked_label(sema, &text, ty)
+        })
+}
+
+/// Like `type_label`, but without the assoc type shortenings, which would
+/// turn the concrete type behind an `impl Trait` back into the trait.
+fn concrete_type_label(
+    sema: &Semantics<RootDatabase>,
+    config: &InlayHintsConfig,
+    ty: &hir::Type,
+) -> InlayHintLabel {
+    if config.lazy_labels {
+        return InlayHintLabel::default();
+    }
+    let text = render_type(sema.db, config, ty, config.max_length);
+    linked_label(sema, &text, ty)
+}
+
+/// Splits the rendered (and possibly truncated) `text` of `ty` into parts,
+/// linking every identifier that names one of the ADTs mentioned by `ty`.
+///
+/// `Type::walk` visits ADTs in the order they are rendered, so each name is
+/// looked up after the previous match. Names dropped by truncation are
+/// skipped over.
+fn linked_label(sema: &Semantics<RootDatabase>, text: &str, ty: &hir::Type) -> InlayHintLabel {
+    let db = sema.db;
+    let mut adts = Vec::new();
+    ty.walk(db, |ty| {
+        if let Some(adt) = ty.as_adt() {
+            adts.push(adt);
+        }
+    });
+
+    let mut label = InlayHintLabel::default();
+    let mut next_adt = 0;
+    let mut rest = text;
+    while let Some(start) = rest.find(|c: char| c.is_alphabetic() || c == '_') {
+        let len = rest[start..]
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len() - start);
+        let ident = &rest[start..start + len];
+        label.push_str(&rest[..start]);
+        let found = adts[next_adt..].iter().position(|adt| adt.name(db).to_string() == ident);
+        match found {
+            Some(idx) => {
+                let goto = adt_name_range(db, adts[next_adt + idx]);
+                label.parts.push(InlayHintLabelPart { text: ident.to_string(), goto: Some(goto) });
+                next_adt += idx + 1;
+            }
+            None => label.push_str(ident),
+        }
+        rest = &rest[start + len..];
+    }
+    label.push_str(rest);
+    label
+}
+
+/// The name of `adt` in its definition, or the whole definition if it has
+/// none, like the focus range of its navigation target.
+fn adt_name_range(db: &RootDatabase, adt: hir::Adt) -> FileRange {
+    match adt {
+        hir::Adt::Struct(it) => name_range(db, it.source(db)),
+        hir::Adt::Union(it) => name_range(db, it.source(db)),
+        hir::Adt::Enum(it) => name_range(db, it.source(db)),
+    }
+}
+
+fn name_range(db: &RootDatabase, src: hir::InFile<impl ast::NameOwner>) -> FileRange {
+    let frange = hir::original_range(db, src.as_ref().map(|it| it.syntax()));
+    match src.value.name() {
+        Some(name) => {
+            let range = hir::original_range(db, src.with_value(name.syntax())).range;
+            FileRange { file_id: frange.file_id, range }
+        }
+        None => frange,
+    }
+}
+
+/// The full, untruncated type, unlike the label.
+fn type_tooltip(
+    sema: &Semantics<RootDatabase>,
+    config: &InlayHintsConfig,
+    ty: &hir::Type,
+) -> Option<Markup> {
+    if !config.hint_tooltips || config.lazy_labels {
+        return None;
+    }
+    Some(Markup::fenced_block(&ty.display(sema.db)))
+}
+
+/// Lookups shared by all the hints of a single pass: the traits and
+/// associated types of `ASSOC_SHORTENINGS` per crate, from the query that
+/// keeps them across passes, and the types of the expressions looked at so
+/// far.
+#[derive(Default, Clone)]
+struct PassCache {
+    assoc_defs: RefCell<FxHashMap<hir::Crate, Arc<Vec<Option<AssocDefs>>>>>,
+    expr_types: RefCell<FxHashMap<SyntaxNodePtr, Option<hir::Type>>>,
+}
+
+impl PassCache {
+    fn type_of_expr(&self, sema: &Semantics<RootDatabase>, expr: &ast::Expr) -> Option<hir::Type> {
+        let ptr = SyntaxNodePtr::new(expr.syntax());
+        if let Some(ty) = self.expr_types.borrow().get(&ptr) {
+            return ty.clone();
+        }
+        let ty = sema.type_of_expr(expr);
+        self.expr_types.borrow_mut().insert(ptr, ty.clone());
+        ty
+    }
+
+    /// The trait and associated type of `ASSOC_SHORTENINGS[idx]`.
+    fn assoc_defs(
+        &self,
+        sema: &Semantics<RootDatabase>,
+        krate: hir::Crate,
+        idx: usize,
+    ) -> Option<AssocDefs> {
+        let mut assoc_defs = self.assoc_defs.borrow_mut();
+        let defs = assoc_defs.entry(krate).or_insert_with(|| sema.db.assoc_shortening_defs(krate));
+        defs[idx]
+    }
+}
+
+pub(super) fn assoc_shortening_defs(
+    db: &dyn InlayHintsDatabase,
+    krate: hir::Crate,
+) -> Arc<Vec<Option<AssocDefs>>> {
+    let sema = Semantics::new(db.upcast());
+    let defs = (0..ASSOC_SHORTENINGS.len()).map(|idx| shortening_defs(&sema, krate, idx));
+    Arc::new(defs.collect())
+}
+
+fn shortening_defs(
+    sema: &Semantics<RootDatabase>,
+    krate: hir::Crate,
+    idx: usize,
+) -> Option<AssocDefs> {
+    let db = sema.db;
+    let shortening = &ASSOC_SHORTENINGS[idx];
+    let trait_ = (shortening.trait_)(&FamousDefs(sema, krate))?;
+    let module = (shortening.module)(&FamousDefs(sema, krate))?;
+    // `FamousDefs` finds `core` through the dependencies of `krate`, so
+    // only shorten types of `core` itself and of crates like `std` that
+    // re-export its module
+    let core = trait_.module(db).krate();
+    if core.declaration_name(db).as_deref() != Some("core") {
+        return None;
+    }
+    if krate != core && !reexports_trait(db, krate, module, trait_) {
+        return None;
+    }
+    // assert the trait is the one exported by its module
+    module.visibility_of(db, &trait_.into()).filter(|&vis| vis == hir::Visibility::Public)?;
+    let assoc_type = trait_.items(db).into_iter().find_map(|item| match item {
+        hir::AssocItem::TypeAlias(alias) if alias.name(db).to_string() == shortening.assoc_type => {
+            Some(alias)
+        }
+        _ => None,
+    })?;
+    Some((trait_, assoc_type))
+}
+
+pub(super) type AssocDefs = (hir::Trait, hir::TypeAlias);
+
+/// Whether `krate` publicly exposes `trait_` under the name of `module`,
+/// either by re-exporting `module` as is or through a module of its own.
+fn reexports_trait(
+    db: &RootDatabase,
+    krate: hir::Crate,
+    module: hir::Module,
+    trait_: hir::Trait,
+) -> bool {
+    let name = match module.name(db) {
+        Some(it) => it,
+        None => return false,
+    };
+    let exports_trait = |reexport: hir::Module| {
+        reexport.scope(db, Some(module)).into_iter().any(|(_, def)| match def {
+            hir::ScopeDef::ModuleDef(hir::ModuleDef::Trait(it)) => it == trait_,
+            _ => false,
+        })
+    };
+    krate.root_module(db).scope(db, Some(module)).into_iter().any(|(it, def)| match def {
+        hir::ScopeDef::ModuleDef(hir::ModuleDef::Module(reexport)) if it == name => {
+            reexport == module || exports_trait(reexport)
+        }
+        _ => false,
+    })
+}
+
+/// A trait whose implementors are rendered as `impl Trait<Assoc = T>` in type
+/// hints, instead of their own, usually unwieldy, types.
+struct AssocShortening {
+    label_start: &'static str,
+    trait_: fn(&FamousDefs) -> Option<hir::Trait>,
+    /// The module the trait is public in.
+    module: fn(&FamousDefs) -> Option<hir::Module>,
+    assoc_type: &'static str,
+}
+
+const ASSOC_SHORTENINGS: &[AssocShortening] = &[
+    AssocShortening {
+        label_start: "impl Iterator<Item = ",
+        trait_: |defs| defs.core_iter_Iterator(),
+        module: |defs| defs.core_iter(),
+        assoc_type: "Item",
+    },
+    AssocShortening {
+        label_start: "impl Future<Output = ",
+        trait_: |defs| defs.core_future_Future(),
+        module: |defs| defs.core_future(),
+        assoc_type: "Output",
+    },
+];
+
+const TRUNCATION: &str = "…";
+
+/// Cuts `text` down to at most `max_chars` characters, ending it with an
+/// ellipsis if anything was cut.
+fn truncate_chars(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    match max_chars.checked_sub(1) {
+        Some(keep) => text.chars().take(keep).chain(TRUNCATION.chars()).collect(),
+        None => String::new(),
+    }
+}
+
+/// Renders `ty` in about `max_length` characters, cut down according to
+/// `config.truncation_policy`. All the types of hint labels go through here.
+fn render_type(
+    db: &RootDatabase,
+    config: &InlayHintsConfig,
+    ty: &hir::Type,
+    max_length: Option<usize>,
+) -> String {
+    let display = |max_length| {
+        ty.display_truncated(db, max_length)
+            .with_default_args(config.show_default_generic_args)
+            .with_max_generic_args(config.max_generic_args)
+            .with_erased_generic_args(config.minimal_type_hints)
+            .with_normalized_projections(config.normalize_projections)
+            .with_closure_style(config.closure_style)
+            .to_string()
+    };
+    let max_length = match max_length {
+        Some(it)
+            if config.collapse_dyn_bounds
+                || config.truncation_policy != TruncationPolicy::Chars =>
+        {
+            it
+        }
+        _ => return display(max_length),
+    };
+    let mut text = display(None);
+    if text.chars().count() <= max_length {
+        return text;
+    }
+    if config.collapse_dyn_bounds {
+        text = collapse_dyn_bounds(&text);
+        if text.chars().count() <= max_length {
+            return text;
+        }
+    }
+    let truncated = match config.truncation_policy {
+        TruncationPolicy::Chars => None,
+        TruncationPolicy::Delimiters => cut_at_generic_arg(&text, max_length),
+        TruncationPolicy::Depth => collapse_nested_args(&text, max_length),
+    };
+    truncated.unwrap_or_else(|| truncate_chars(&text, max_length))
+}
+
+/// `text` with the bounds of each trait object after the first replaced by
+/// an ellipsis, as in `Pin<Box<dyn Future<Output = u8> + …>>`.
+fn collapse_dyn_bounds(text: &str) -> String {
+    let mut res = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(idx) = find_dyn(rest) {
+        let (before, after) = rest.split_at(idx + "dyn ".len());
+        res.push_str(before);
+        let first_end = bound_end(after);
+        res.push_str(&collapse_dyn_bounds(&after[..first_end]));
+        let mut end = first_end;
+        while after[end..].starts_with(" + ") {
+            end += " + ".len();
+            end += bound_end(&after[end..]);
+        }
+        if end != first_end {
+            res.push_str(" + ");
+            res.push_str(TRUNCATION);
+        }
+        rest = &after[end..];
+    }
+    res.push_str(rest);
+    res
+}
+
+/// The offset of the first `dyn ` of `text` that starts a word.
+fn find_dyn(text: &str) -> Option<usize> {
+    text.match_indices("dyn ").map(|(idx, _)| idx).find(|&idx| {
+        !matches!(text[..idx].chars().next_back(), Some(c) if c.is_alphanumeric() || c == '_')
+    })
+}
+
+/// The length of the trait bound `text` starts with, which ends at a ` + `,
+/// a comma or a closing bracket outside of its own brackets.
+fn bound_end(text: &str) -> usize {
+    let mut depth = 0usize;
+    let mut prev = None;
+    for (idx, c) in text.char_indices() {
+        if closing_bracket(c).is_some() {
+            depth += 1;
+        } else if is_closing_bracket(c, prev) {
+            match depth.checked_sub(1) {
+                Some(it) => depth = it,
+                None => return idx,
+            }
+        } else if depth == 0 && (c == ',' || text[idx..].starts_with(" + ")) {
+            return idx;
+        }
+        prev = Some(c);
+    }
+    text.len()
+}
+
+fn closing_bracket(c: char) -> Option<char> {
+    match c {
+        '<' => Some('>'),
+        '(' => Some(')'),
+        '[' => Some(']'),
+        _ => None,
+    }
+}
+
+/// Whether `c` closes a bracket, as opposed to ending the `->` of a function
+/// type.
+fn is_closing_bracket(c: char, prev: Option<char>) -> bool {
+    match c {
+        ')' | ']' => true,
+        '>' => prev != Some('-'),
+        _ => false,
+    }
+}
+
+/// The longest prefix of `text` that ends at the start of a generic argument
+/// and fits in `max_length` along with an ellipsis and the brackets it
+/// leaves open, as in `Outer<Middle<…>>`.
+fn cut_at_generic_arg(text: &str, max_length: usize) -> Option<String> {
+    let mut open = Vec::new();
+    let mut prev = None;
+    let mut res = None;
+    for (idx, c) in text.char_indices() {
+        let at_arg_start =
+            matches!(prev, Some('<') | Some('(') | Some('[')) || text[..idx].ends_with(", ");
+        if at_arg_start && !open.is_empty() {
+            let closing = open.iter().rev().collect::<String>();
+            let candidate = format!("{}{}{}", &text[..idx], TRUNCATION, closing);
+            if candidate.chars().count() <= max_length {
+                res = Some(candidate);
+            }
+        }
+        if let Some(closing) = closing_bracket(c) {
+            open.push(closing);
+        } else if is_closing_bracket(c, prev) {
+            open.pop();
+        }
+        prev = Some(c);
+    }
+    res
+}
+
+/// `text` with everything nested deeper than `depth` brackets replaced by an
+/// ellipsis, as in `Outer<Middle<…>, u32>` for a depth of 1.
+fn collapse_below(text: &str, depth: usize) -> String {
+    let mut res = String::new();
+    let mut current = 0usize;
+    let mut prev = None;
+    for c in text.chars() {
+        if is_closing_bracket(c, prev) {
+            current = current.saturating_sub(1);
+        }
+        if current <= depth {
+            res.push(c);
+        } else if !res.ends_with(TRUNCATION) {
+            res.push_str(TRUNCATION);
+        }
+        if closing_bracket(c).is_some() {
+            current += 1;
+        }
+        prev = Some(c);
+    }
+    res
+}
+
+/// Collapses the most deeply nested generic arguments of `text`, one level
+/// at a time, until it fits in `max_length`.
+fn collapse_nested_args(text: &str, max_length: usize) -> Option<String> {
+    let mut max_depth = 0;
+    let mut current = 0usize;
+    let mut prev = None;
+    for c in text.chars() {
+        if closing_bracket(c).is_some() {
+            current += 1;
+            max_depth = max_depth.max(current);
+        } else if is_closing_bracket(c, prev) {
+            current = current.saturating_sub(1);
+        }
+        prev = Some(c);
+    }
+    (0..max_depth)
+        .rev()
+        .map(|depth| collapse_below(text, depth))
+        .find(|it| it.chars().count() <= max_length)
+}
+
+/// Replaces the hint of a type from `core` that implements one of the traits of
+/// `shortenings` with the likes of `impl Iterator<Item = Ty>`.
+fn hint_assoc_shorten(
+    sema: &Semantics<RootDatabase>,
+    cache: &PassCache,
+    config: &InlayHintsConfig,
+    ty: &hir::Type,
+    shortenings: &[AssocShortening],
+) -> Option<InlayHintLabel> {
+    let db = sema.db;
+    let strukt = std::iter::successors(Some(ty.clone()), |ty| ty.remove_ref())
+        .last()
+        .and_then(|strukt| strukt.as_adt())?;
+    let krate = strukt.krate(db)?;
+    for (idx, shortening) in shortenings.iter().enumerate() {
+        let (trait_, assoc_type) = match cache.assoc_defs(sema, krate, idx) {
+            Some(it) => it,
+            None => continue,
+        };
+        if !ty.impls_trait(db, trait_, &[]) {
+            continue;
+        }
+        let ty = match ty.normalize_trait_assoc_type(db, trait_, &[], assoc_type) {
+            Some(it) => it,
+            None => continue,
+        };
+        let label_start = shortening.label_start;
+        const LABEL_END: &str = ">";
+        let wrapper_len = label_start.len() + LABEL_END.len();
+
+        // `display_truncated` may overshoot its budget, so the assoc type is
+        // cut down to it afterwards. When not even an ellipsis fits in
+        // between, the whole label is cut instead.
+        let budget = match config.max_length {
+            Some(max_length) if max_length <= wrapper_len => {
+                let full = format!("{}{}{}", label_start, TRUNCATION, LABEL_END);
+                return Some(truncate_chars(&full, max_length).into());
+            }
+            max_length => max_length.map(|len| len - wrapper_len),
+        };
+        let mut ty_display = render_type(db, config, &ty, budget);
+        if let Some(budget) = budget {
+            ty_display = truncate_chars(&ty_display, budget);
+        }
+        let mut label = InlayHintLabel::from(label_start);
+        label.append(linked_label(sema, &ty_display, &ty));
+        label.push_str(LABEL_END);
+        return Some(label);
+    }
+
+    None
+}
+
+/// Renders `Result<Ok, E>` as `Result<Ok<…>, E>` when `config.result_shortening`
+/// is set, `Ok` has generic arguments of its own and the full type would not
+/// fit in `config.max_length`.
+fn hint_result_shorten(
+    sema: &Semantics<RootDatabase>,
+    config: &InlayHintsConfig,
+    ty: &hir::Type,
+) -> Option<InlayHintLabel> {
+    if !config.result_shortening {
+        return None;
+    }
+    let max_length = config.max_length?;
+    let db = sema.db;
+    let result = match ty.as_adt()? {
+        hir::Adt::Enum(it) => it,
+        _ => return None,
+    };
+    if Some(result) != FamousDefs(sema, result.module(db).krate()).core_result_Result() {
+        return None;
+    }
+    let (ok, err) = match ty.type_parameters().as_slice() {
+        [ok, err] => (ok.clone(), err.clone()),
+        _ => return None,
+    };
+    let ok_adt = ok.as_adt()?;
+    if ok.type_parameters().is_empty() {
+        return None;
+    }
+    if render_type(db, config, ty, None).chars().count() <= max_length {
+        return None;
+    }
+
+    let err = render_type(db, config, &err, Some(max_length));
+    let text = format!("{}<{}<{}>, {}>", result.name(db), ok_adt.name(db), TRUNCATION, err);
+    Some(linked_label(sema, &truncate_chars(&text, max_length), ty))
+}
+
+fn pat_is_enum_variant(db: &RootDatabase, bind_pat: &ast::IdentPat, pat_ty: &hir::Type) -> bool {
+    if let Some(hir::Adt::Enum(enum_data)) = pat_ty.as_adt() {
+        let pat_text = bind_pat.to_string();
+        enum_data
+            .variants(db)
+            .into_iter()
+            .map(|variant| variant.name(db).to_string())
+            .any(|enum_name| enum_name == pat_text)
+    } else {
+        false
+    }
+}
+
+fn should_not_display_type_hint(
+    sema: &Semantics<RootDatabase>,
+    cache: &PassCache,
+    bind_pat: &ast::IdentPat,
+    pat_ty: &hir::Type,
+) -> bool {
+    let db = sema.db;
+
+    if pat_ty.is_unknown() {
+        return true;
+    }
+
+    if let Some(hir::Adt::Struct(s)) = pat_ty.as_adt() {
+        if s.fields(db).is_empty() && s.name(db).to_string() == bind_pat.to_string() {
+            return true;
+        }
+    }
+
+    // Only the nearest node whose own pattern holds the binding decides, so
+    // that the bindings of nested `if let`s aren't judged by an outer level.
+    let in_pat = |pat: Option<ast::Pat>| match pat {
+        Some(pat) => pat.syntax().text_range().contains_range(bind_pat.syntax().text_range()),
+        None => false,
+    };
+    for node in bind_pat.syntax().ancestors() {
+        match_ast! {
+            match node {
+                ast::LetStmt(it) => {
+                    if !in_pat(it.pat()) {
+                        continue;
+                    }
+                    // Annotations with placeholders, like `Vec<_>`, still
+                    // leave part of the type to inference.
+                    return match it.ty() {
+                        Some(ty) => !ty
+                            .syntax()
+                            .descendants()
+                            .any(|it| ast::InferType::can_cast(it.kind())),
+                        // Turbofishes are annotations too.
+                        None => match it.initializer() {
+                            Some(init) => turbofish_spells_type(db, &init, pat_ty),
+                            None => false,
+                        },
+                    };
+                },
+                ast::Param(it) => {
+                    if in_pat(it.pat()) {
+                        return it.ty().is_some();
+                    }
+                },
+                ast::MatchArm(it) => {
+                    if in_pat(it.pat()) {
+                        return pat_is_enum_variant(db, bind_pat, pat_ty);
+                    }
+                },
+                ast::IfExpr(it) => {
+                    if in_pat(it.condition().and_then(|condition| condition.pat())) {
+                        return pat_is_enum_variant(db, bind_pat, pat_ty);
+                    }
+                },
+                ast::WhileExpr(it) => {
+                    if in_pat(it.condition().and_then(|condition| condition.pat())) {
+                        return pat_is_enum_variant(db, bind_pat, pat_ty);
+                    }
+                },
+                ast::ForExpr(it) => {
+                    if !in_pat(it.pat()) {
+                        continue;
+                    }
+                    // We *should* display hint only if user provided "in {expr}" and we know the type of expr (and it's not unit).
+                    // Type of expr should be iterable.
+                    return it.in_token().is_none() ||
+                        it.iterable()
+                            .and_then(|iterable_expr| cache.type_of_expr(sema, &iterable_expr))
+                            .map(|iterable_ty| iterable_ty.is_unknown() || iterable_ty.is_unit())
+                            .unwrap_or(true)
+                },
+                _ => (),
+            }
+        }
+    }
+    false
+}
+
+/// Whether `expr` is a call whose turbofish spells out exactly `ty`, like
+/// `Vec::<i32>::new()` or `text.parse::<u32>()`.
+fn turbofish_spells_type(db: &RootDatabase, expr: &ast::Expr, ty: &hir::Type) -> bool {
+    let spelled = match expr {
+        ast::Expr::CallExpr(call) => {
+            let path = match call.expr() {
+                Some(ast::Expr::PathExpr(it)) => it.path(),
+                _ => None,
+            };
+            std::iter::successors(path, |path| path.qualifier())
+                .filter_map(|path| {
+                    let segment = path.segment()?;
+                    segment.generic_arg_list()?;
+                    segment_spelling(&segment)
+                })
+                .collect::<Vec<_>>()
+        }
+        ast::Expr::MethodCallExpr(call) => {
+            let args = match call.generic_arg_list() {
+                Some(it) => it.generic_args().collect::<Vec<_>>(),
+                None => return false,
+            };
+            match args.as_slice() {
+                [ast::GenericArg::TypeArg(arg)] => vec![arg.syntax().to_string()],
+                _ => return false,
+            }
+        }
+        _ => return false,
+    };
+    spelled.iter().any(|it| spells_type(db, it, ty))
+}
+
+/// Whether `expr` is a struct literal, or a `new` or `default` call, of a type
+/// spelled out exactly like `ty` renders, as in `Foo { .. }` or `Foo::new()`.
+fn is_obvious_constructor(db: &RootDatabase, expr: &ast::Expr, ty: &hir::Type) -> bool {
+    let path = match expr {
+        ast::Expr::RecordExpr(it) => it.path(),
+        ast::Expr::CallExpr(it) => match it.expr() {
+            Some(ast::Expr::PathExpr(callee)) => callee.path().and_then(|path| {
+                let name = path.segment()?.name_ref()?;
+                if matches!(name.text().as_str(), "new" | "default") {
+                    path.qualifier()
+                } else {
+                    None
+                }
+            }),
+            _ => None,
+        },
+        _ => None,
+    };
+    match path.and_then(|it| it.segment()).and_then(|it| segment_spelling(&it)) {
+        Some(spelled) => spells_type(db, &spelled, ty),
+        None => false,
+    }
+}
+
+/// Whether `let_stmt` fits on one line and is initialized with a path whose
+/// first segment is the name of `ty`, like `MyEnum::Variant` or `Foo::CONST`.
+fn is_type_named_in_rhs(db: &RootDatabase, let_stmt: &ast::LetStmt, ty: &hir::Type) -> bool {
+    if let_stmt.syntax().text().contains_char('\n') {
+        return false;
+    }
+    let path = match let_stmt.initializer() {
+        Some(ast::Expr::PathExpr(it)) => it.path(),
+        _ => None,
+    };
+    let first_segment = std::iter::successors(path, |path| path.qualifier())
+        .last()
+        .and_then(|it| it.segment())
+        .and_then(|it| it.name_ref());
+    match (first_segment, ty.as_adt()) {
+        (Some(name), Some(adt)) => name.text().as_str() == adt.name(db).to_string(),
+        _ => false,
+    }
+}
+
+/// `segment` written as a type, like `Vec<i32>` for `Vec::<i32>`.
+fn segment_spelling(segment: &ast::PathSegment) -> Option<String> {
+    let name = segment.name_ref()?;
+    let args = segment.generic_arg_list().map(|it| it.syntax().to_string()).unwrap_or_default();
+    Some(format!("{}{}", name, args.trim_start_matches("::")))
+}
+
+fn spells_type(db: &RootDatabase, text: &str, ty: &hir::Type) -> bool {
+    let without_whitespace =
+        |text: &str| text.chars().filter(|c| !c.is_whitespace()).collect::<String>();
+    without_whitespace(text) == without_whitespace(&ty.display(db).to_string())
+}
+
+fn should_show_param_name_hint(
+    sema: &Semantics<RootDatabase>,
+    cache: &PassCache,
+    callable: &hir::Callable,
+    param_name: &str,
+    argument: &ast::Expr,
+) -> bool {
+    let param_name = param_name.trim_start_matches('_');
+    let fn_name = match callable.kind() {
+        hir::CallableKind::Function(it) => Some(it.name(sema.db).to_string()),
+        hir::CallableKind::TupleStruct(_)
+        | hir::CallableKind::TupleEnumVariant(_)
+        | hir::CallableKind::Closure => None,
+    };
+    if param_name.is_empty()
+        || Some(param_name) == fn_name.as_ref().map(|s| s.trim_start_matches('_'))
+        || is_argument_similar_to_param_name(sema, cache, argument, param_name)
+        || param_name.starts_with("ra_fixture")
+    {
+        return false;
+    }
+
+    // avoid displaying hints for common functions like map, filter, etc.
+    // or other obvious words used in std
+    !(callable.n_params() == 1 && is_obvious_param(param_name))
+}
+
+fn is_argument_similar_to_param_name(
+    sema: &Semantics<RootDatabase>,
+    cache: &PassCache,
+    argument: &ast::Expr,
+    param_name: &str,
+) -> bool {
+    if is_enum_name_similar_to_param_name(sema, cache, argument, param_name) {
+        return true;
+    }
+    match get_string_representation(argument) {
+        None => false,
+        Some(repr) => {
+            let argument_string = repr.trim_start_matches("r#").trim_start_matches('_');
+            argument_string.starts_with(param_name) || argument_string.ends_with(param_name)
+        }
+    }
+}
+
+fn is_enum_name_similar_to_param_name(
+    sema: &Semantics<RootDatabase>,
+    cache: &PassCache,
+    argument: &ast::Expr,
+    param_name: &str,
+) -> bool {
+    match cache.type_of_expr(sema, argument).and_then(|t| t.as_adt()) {
+        Some(hir::Adt::Enum(e)) => to_lower_snake_case(&e.name(sema.db).to_string()) == param_name,
+        _ => false,
+    }
+}
+
+fn get_string_representation(expr: &ast::Expr) -> Option<String> {
+    match expr {
+        ast::Expr::MethodCallExpr(method_call_expr) => {
+            Some(method_call_expr.name_ref()?.to_string())
+        }
+        ast::Expr::RefExpr(ref_expr) => get_string_representation(&ref_expr.expr()?),
+        _ => Some(expr.to_string()),
+    }
+}
+
+fn is_obvious_param(param_name: &str) -> bool {
+    let is_obvious_param_name =
+        matches!(param_name, "predicate" | "value" | "pat" | "rhs" | "other");
+    param_name.len() == 1 || is_obvious_param_name
+}
+
+fn get_callable(
+    sema: &Semantics<RootDatabase>,
+    cache: &PassCache,
+    expr: &ast::Expr,
+) -> Option<hir::Callable> {
+    match expr {
+        ast::Expr::CallExpr(expr) => cache.type_of_expr(sema, &expr.expr()?)?.as_callable(sema.db),
+        ast::Expr::MethodCallExpr(expr) => sema.resolve_method_call_as_callable(expr),
+        _ => None,
+    }
+}
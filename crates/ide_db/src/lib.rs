@@ -1,8 +1,10 @@
 //! This crate defines the core datastructure representing IDE state -- `RootDatabase`.
 //!
-//! It is mainly a `HirDatabase` for semantic analysis, plus a `SymbolsDatabase`, for fuzzy search.
+//! It is mainly a `HirDatabase` for semantic analysis, plus a `SymbolsDatabase`, for fuzzy search,
+//! and an `InlayHintsDatabase`, which caches inlay hints.
 
 mod apply_change;
+pub mod inlay_hints;
 pub mod label;
 pub mod line_index;
 pub mod symbol_index;
@@ -10,6 +12,8 @@ pub mod defs;
 pub mod search;
 pub mod imports_locator;
 pub mod source_change;
+pub mod markup;
+pub mod helpers;
 
 use std::{fmt, sync::Arc};
 
@@ -28,6 +32,7 @@ use crate::{line_index::LineIndex, symbol_index::SymbolsDatabase};
     base_db::SourceDatabaseExtStorage,
     LineIndexDatabaseStorage,
     symbol_index::SymbolsDatabaseStorage,
+    inlay_hints::InlayHintsDatabaseStorage,
     hir::db::InternDatabaseStorage,
     hir::db::AstDatabaseStorage,
     hir::db::DefDatabaseStorage,
@@ -61,6 +66,12 @@ impl Upcast<dyn HirDatabase> for RootDatabase {
     }
 }
 
+impl Upcast<RootDatabase> for RootDatabase {
+    fn upcast(&self) -> &RootDatabase {
+        self
+    }
+}
+
 impl FileLoader for RootDatabase {
     fn file_text(&self, file_id: FileId) -> Arc<String> {
         FileLoaderDelegate(self).file_text(file_id)
@@ -109,6 +120,9 @@ impl RootDatabase {
         base_db::ParseQuery.in_db_mut(self).set_lru_capacity(lru_capacity);
         hir::db::ParseMacroQuery.in_db_mut(self).set_lru_capacity(lru_capacity);
         hir::db::MacroExpandQuery.in_db_mut(self).set_lru_capacity(lru_capacity);
+        inlay_hints::ItemInlayHintsQuery
+            .in_db_mut(self)
+            .set_lru_capacity(lru_capacity * inlay_hints::ITEMS_PER_FILE);
     }
 }
 
@@ -79,6 +79,8 @@ analysis-bench <PATH> Benchmark specific analysis operation
     <PATH>            Directory with Cargo.toml
     --highlight <PATH>
                       Compute syntax highlighting for this file
+    --inlay-hints <PATH>
+                      Compute inlay hints for this file
     --complete <PATH:LINE:COLUMN>
                       Compute completions at this location
     --goto-def <PATH:LINE:COLUMN>
@@ -162,19 +164,25 @@ impl Args {
                 what: {
                     let highlight_path: Option<String> =
                         matches.opt_value_from_str("--highlight")?;
+                    let inlay_hints_path: Option<String> =
+                        matches.opt_value_from_str("--inlay-hints")?;
                     let complete_path: Option<Position> =
                         matches.opt_value_from_str("--complete")?;
                     let goto_def_path: Option<Position> =
                         matches.opt_value_from_str("--goto-def")?;
-                    match (highlight_path, complete_path, goto_def_path) {
-                            (Some(path), None, None) => {
+                    match (highlight_path, inlay_hints_path, complete_path, goto_def_path) {
+                            (Some(path), None, None, None) => {
                                 let path = env::current_dir().unwrap().join(path);
                                 BenchWhat::Highlight { path: AbsPathBuf::assert(path) }
                             }
-                            (None, Some(position), None) => BenchWhat::Complete(position),
-                            (None, None, Some(position)) => BenchWhat::GotoDef(position),
+                            (None, Some(path), None, None) => {
+                                let path = env::current_dir().unwrap().join(path);
+                                BenchWhat::InlayHints { path: AbsPathBuf::assert(path) }
+                            }
+                            (None, None, Some(position), None) => BenchWhat::Complete(position),
+                            (None, None, None, Some(position)) => BenchWhat::GotoDef(position),
                             _ => panic!(
-                                "exactly one of  `--highlight`, `--complete` or `--goto-def` must be set"
+                                "exactly one of  `--highlight`, `--inlay-hints`, `--complete` or `--goto-def` must be set"
                             ),
                         }
                 },
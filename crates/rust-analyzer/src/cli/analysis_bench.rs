@@ -8,7 +8,8 @@ use base_db::{
     FileId,
 };
 use ide::{
-    Analysis, AnalysisHost, Change, CompletionConfig, DiagnosticsConfig, FilePosition, LineCol,
+    Analysis, AnalysisHost, Change, CompletionConfig, DiagnosticsConfig, FilePosition,
+    InlayHintsConfig, LineCol,
 };
 use vfs::AbsPathBuf;
 
@@ -27,6 +28,7 @@ pub struct BenchCmd {
 
 pub enum BenchWhat {
     Highlight { path: AbsPathBuf },
+    InlayHints { path: AbsPathBuf },
     Complete(Position),
     GotoDef(Position),
 }
@@ -63,7 +65,7 @@ impl BenchCmd {
 
         let file_id = {
             let path = match &self.what {
-                BenchWhat::Highlight { path } => path,
+                BenchWhat::Highlight { path } | BenchWhat::InlayHints { path } => path,
                 BenchWhat::Complete(pos) | BenchWhat::GotoDef(pos) => &pos.path,
             };
             let path = path.clone().into();
@@ -80,6 +82,15 @@ impl BenchCmd {
                     println!("\n{}", res);
                 }
             }
+            BenchWhat::InlayHints { .. } => {
+                let config = InlayHintsConfig::default();
+                let res = do_work(&mut host, file_id, |analysis| {
+                    analysis.inlay_hints(file_id, &config).unwrap()
+                });
+                if verbosity.is_verbose() {
+                    println!("\n{:#?}", res);
+                }
+            }
             BenchWhat::Complete(pos) | BenchWhat::GotoDef(pos) => {
                 let is_completion = matches!(self.what, BenchWhat::Complete(..));
 
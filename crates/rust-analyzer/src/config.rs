@@ -10,10 +10,10 @@
 use std::{ffi::OsString, path::PathBuf};
 
 use flycheck::FlycheckConfig;
-use hir::PrefixKind;
+use hir::{ClosureStyle, PrefixKind};
 use ide::{
-    AssistConfig, CompletionConfig, DiagnosticsConfig, HoverConfig, InlayHintsConfig,
-    MergeBehaviour,
+    AssistConfig, ChainingHintsAnchor, CompletionConfig, DiagnosticsConfig, HintRenderTemplates,
+    HintTemplate, HoverConfig, InlayHintsConfig, MergeBehaviour, TruncationPolicy,
 };
 use lsp_types::{ClientCapabilities, MarkupKind};
 use project_model::{CargoConfig, ProjectJson, ProjectJsonData, ProjectManifest};
@@ -180,8 +180,47 @@ impl Config {
             inlay_hints: InlayHintsConfig {
                 type_hints: true,
                 parameter_hints: true,
+                self_param_hints: true,
+                self_param_hints_only_when_ufcs: true,
+                tuple_constructor_hints: false,
                 chaining_hints: true,
+                question_mark_chain_hints: false,
+                dedup_adjacent_chaining_hints: false,
+                chaining_hints_on_change_only: false,
+                chaining_hints_on_record_literals: false,
+                chaining_hints_anchor: ChainingHintsAnchor::Receiver,
+                question_mark_conversion_hints: false,
+                index_output_hints: false,
+                operator_hints: false,
+                deref_hints: false,
+                arg_list_type_hints: false,
+                literal_type_hints: false,
+                inferred_constructor_type_hints: false,
+                combine_colocated_hints: false,
+                hint_function_filter: None,
+                surprise_inference_hints: false,
+                adjustment_hints: false,
+                arg_order_mismatch_hints: false,
+                closure_capture_hints: false,
+                tuple_struct_field_doc_hints: false,
+                show_lifetimes_in_hints: false,
+                show_default_generic_args: false,
+                result_shortening: false,
+                hide_string_type_hints: false,
+                hide_hints_for_obvious_constructors: false,
+                hide_hints_when_type_in_rhs: false,
+                expand_impl_trait_hints: false,
                 max_length: None,
+                truncation_policy: TruncationPolicy::Chars,
+                collapse_dyn_bounds: false,
+                max_generic_args: None,
+                minimal_type_hints: false,
+                normalize_projections: false,
+                closure_style: ClosureStyle::RANotation,
+                lazy_labels: false,
+                hint_tooltips: false,
+                hint_ids: false,
+                render_templates: HintRenderTemplates::EMPTY,
             },
             completion: CompletionConfig {
                 enable_postfix_completions: true,
@@ -277,8 +316,72 @@ impl Config {
         self.inlay_hints = InlayHintsConfig {
             type_hints: data.inlayHints_typeHints,
             parameter_hints: data.inlayHints_parameterHints,
+            self_param_hints: data.inlayHints_selfParameterHints,
+            self_param_hints_only_when_ufcs: data.inlayHints_selfParameterHintsOnlyWhenUfcs,
+            tuple_constructor_hints: data.inlayHints_tupleConstructorHints,
             chaining_hints: data.inlayHints_chainingHints,
+            question_mark_chain_hints: data.inlayHints_questionMarkChainHints,
+            question_mark_conversion_hints: data.inlayHints_questionMarkConversionHints,
+            index_output_hints: data.inlayHints_indexOutputHints,
+            operator_hints: data.inlayHints_operatorHints,
+            deref_hints: data.inlayHints_derefHints,
+            arg_list_type_hints: data.inlayHints_argListTypeHints,
+            literal_type_hints: data.inlayHints_literalTypeHints,
+            inferred_constructor_type_hints: data.inlayHints_inferredConstructorTypeHints,
+            surprise_inference_hints: data.inlayHints_surpriseInferenceHints,
+            adjustment_hints: data.inlayHints_adjustmentHints,
+            arg_order_mismatch_hints: data.inlayHints_argOrderMismatchHints,
+            closure_capture_hints: data.inlayHints_closureCaptureHints,
+            tuple_struct_field_doc_hints: data.inlayHints_tupleStructFieldDocHints,
+            show_lifetimes_in_hints: data.inlayHints_extendedLifetimeHints,
+            show_default_generic_args: data.inlayHints_defaultGenericArgs,
+            result_shortening: data.inlayHints_resultShortening,
+            hide_string_type_hints: data.inlayHints_hideStringTypeHints,
+            hide_hints_for_obvious_constructors: data.inlayHints_hideObviousConstructorHints,
+            hide_hints_when_type_in_rhs: data.inlayHints_hideHintsWhenTypeInRhs,
+            expand_impl_trait_hints: data.inlayHints_expandImplTraitHints,
+            dedup_adjacent_chaining_hints: data.inlayHints_dedupAdjacentChainingHints,
+            chaining_hints_on_change_only: data.inlayHints_chainingHintsOnChangeOnly,
+            chaining_hints_on_record_literals: data.inlayHints_chainingHintsOnRecordLiterals,
+            chaining_hints_anchor: match data.inlayHints_chainingHintsAnchor {
+                ChainingAnchorDef::Receiver => ChainingHintsAnchor::Receiver,
+                ChainingAnchorDef::AfterDot => ChainingHintsAnchor::AfterDot,
+            },
+            combine_colocated_hints: data.inlayHints_combineColocatedHints,
+            hint_function_filter: data.inlayHints_functionFilter,
             max_length: data.inlayHints_maxLength,
+            truncation_policy: match data.inlayHints_truncationPolicy {
+                TruncationPolicyDef::Chars => TruncationPolicy::Chars,
+                TruncationPolicyDef::Delimiters => TruncationPolicy::Delimiters,
+                TruncationPolicyDef::Depth => TruncationPolicy::Depth,
+            },
+            collapse_dyn_bounds: data.inlayHints_collapseDynBounds,
+            max_generic_args: data.inlayHints_maxGenericArgs,
+            minimal_type_hints: data.inlayHints_minimalTypeHints,
+            normalize_projections: data.inlayHints_normalizeProjections,
+            closure_style: match data.inlayHints_closureStyle {
+                ClosureStyleDef::RaNotation => ClosureStyle::RANotation,
+                ClosureStyleDef::ImplFn => ClosureStyle::ImplFn,
+                ClosureStyleDef::ClosureWithId => ClosureStyle::ClosureWithId,
+                ClosureStyleDef::Hide => ClosureStyle::Hide,
+            },
+            lazy_labels: false,
+            hint_tooltips: data.inlayHints_tooltips,
+            hint_ids: data.inlayHints_stableIds,
+            render_templates: {
+                let templates = data.inlayHints_renderTemplates;
+                let template =
+                    |it: HintTemplateDef| HintTemplate { prefix: it.prefix, suffix: it.suffix };
+                HintRenderTemplates {
+                    type_hints: template(templates.type_hints),
+                    parameter_hints: template(templates.parameter_hints),
+                    chaining_hints: template(templates.chaining_hints),
+                    surprise_inference_hints: template(templates.surprise_inference_hints),
+                    adjustment_hints: template(templates.adjustment_hints),
+                    arg_order_mismatch_hints: template(templates.arg_order_mismatch_hints),
+                    closure_capture_hints: template(templates.closure_capture_hints),
+                }
+            },
         };
 
         self.completion.enable_postfix_completions = data.completion_postfix_enable;
@@ -428,6 +531,49 @@ enum ImportPrefixDef {
     ByCrate,
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ChainingAnchorDef {
+    Receiver,
+    AfterDot,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum TruncationPolicyDef {
+    Chars,
+    Delimiters,
+    Depth,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct HintTemplateDef {
+    prefix: String,
+    suffix: String,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct HintRenderTemplatesDef {
+    type_hints: HintTemplateDef,
+    parameter_hints: HintTemplateDef,
+    chaining_hints: HintTemplateDef,
+    surprise_inference_hints: HintTemplateDef,
+    adjustment_hints: HintTemplateDef,
+    arg_order_mismatch_hints: HintTemplateDef,
+    closure_capture_hints: HintTemplateDef,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ClosureStyleDef {
+    RaNotation,
+    ImplFn,
+    ClosureWithId,
+    Hide,
+}
+
 macro_rules! config_data {
     (struct $name:ident { $($field:ident: $ty:ty = $default:expr,)*}) => {
         #[allow(non_snake_case)]
@@ -492,10 +638,48 @@ config_data! {
         hoverActions_run: bool             = true,
         hoverActions_linksInHover: bool    = true,
 
-        inlayHints_chainingHints: bool      = true,
-        inlayHints_maxLength: Option<usize> = None,
-        inlayHints_parameterHints: bool     = true,
-        inlayHints_typeHints: bool          = true,
+        inlayHints_adjustmentHints: bool                   = false,
+        inlayHints_argListTypeHints: bool                  = false,
+        inlayHints_argOrderMismatchHints: bool             = false,
+        inlayHints_chainingHints: bool                     = true,
+        inlayHints_chainingHintsAnchor: ChainingAnchorDef  = ChainingAnchorDef::Receiver,
+        inlayHints_chainingHintsOnChangeOnly: bool         = false,
+        inlayHints_chainingHintsOnRecordLiterals: bool     = false,
+        inlayHints_closureCaptureHints: bool               = false,
+        inlayHints_closureStyle: ClosureStyleDef           = ClosureStyleDef::RaNotation,
+        inlayHints_collapseDynBounds: bool                 = false,
+        inlayHints_combineColocatedHints: bool             = false,
+        inlayHints_dedupAdjacentChainingHints: bool        = false,
+        inlayHints_defaultGenericArgs: bool                = false,
+        inlayHints_derefHints: bool                        = false,
+        inlayHints_expandImplTraitHints: bool              = false,
+        inlayHints_extendedLifetimeHints: bool             = false,
+        inlayHints_functionFilter: Option<String>          = None,
+        inlayHints_hideHintsWhenTypeInRhs: bool            = false,
+        inlayHints_hideObviousConstructorHints: bool       = false,
+        inlayHints_hideStringTypeHints: bool               = false,
+        inlayHints_indexOutputHints: bool                  = false,
+        inlayHints_inferredConstructorTypeHints: bool      = false,
+        inlayHints_literalTypeHints: bool                  = false,
+        inlayHints_maxGenericArgs: Option<usize>           = None,
+        inlayHints_maxLength: Option<usize>                = None,
+        inlayHints_minimalTypeHints: bool                  = false,
+        inlayHints_normalizeProjections: bool              = false,
+        inlayHints_operatorHints: bool                     = false,
+        inlayHints_parameterHints: bool                    = true,
+        inlayHints_questionMarkChainHints: bool            = false,
+        inlayHints_questionMarkConversionHints: bool       = false,
+        inlayHints_renderTemplates: HintRenderTemplatesDef = HintRenderTemplatesDef::default(),
+        inlayHints_resultShortening: bool                  = false,
+        inlayHints_selfParameterHints: bool                = true,
+        inlayHints_selfParameterHintsOnlyWhenUfcs: bool    = true,
+        inlayHints_stableIds: bool                         = false,
+        inlayHints_surpriseInferenceHints: bool            = false,
+        inlayHints_tooltips: bool                          = false,
+        inlayHints_truncationPolicy: TruncationPolicyDef   = TruncationPolicyDef::Chars,
+        inlayHints_tupleConstructorHints: bool             = false,
+        inlayHints_tupleStructFieldDocHints: bool          = false,
+        inlayHints_typeHints: bool                         = true,
 
         lens_debug: bool            = true,
         lens_enable: bool           = true,
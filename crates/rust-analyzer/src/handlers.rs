@@ -1146,11 +1146,13 @@ pub(crate) fn handle_inlay_hints(
     let _p = profile::span("handle_inlay_hints");
     let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
     let line_index = snap.analysis.file_line_index(file_id)?;
+    let line_endings = snap.file_line_endings(file_id);
+    let range = params.range.map(|range| from_proto::text_range(&line_index, range));
     Ok(snap
         .analysis
-        .inlay_hints(file_id, &snap.config.inlay_hints)?
+        .inlay_hints_range(file_id, range, &snap.config.inlay_hints)?
         .into_iter()
-        .map(|it| to_proto::inlay_hint(&line_index, it))
+        .map(|it| to_proto::inlay_hint(&snap, &line_index, line_endings, it))
         .collect())
 }
 
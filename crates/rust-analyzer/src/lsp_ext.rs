@@ -197,6 +197,8 @@ impl Request for InlayHints {
 #[serde(rename_all = "camelCase")]
 pub struct InlayHintsParams {
     pub text_document: TextDocumentIdentifier,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub range: Option<Range>,
 }
 
 #[derive(Debug, PartialEq, Eq, Deserialize, Serialize)]
@@ -204,13 +206,49 @@ pub enum InlayKind {
     TypeHint,
     ParameterHint,
     ChainingHint,
+    SurpriseInferenceHint,
+    AdjustmentHint,
+    ArgOrderMismatchHint,
+    ClosureCaptureHint,
+}
+
+/// The `InlayHintKind` of LSP 3.17, which only tells types from parameters.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct InlayHintKind(i32);
+
+impl InlayHintKind {
+    pub const TYPE: InlayHintKind = InlayHintKind(1);
+    pub const PARAMETER: InlayHintKind = InlayHintKind(2);
 }
 
 #[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct InlayHint {
     pub range: Range,
     pub kind: InlayKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protocol_kind: Option<InlayHintKind>,
     pub label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label_parts: Option<Vec<InlayHintLabelPart>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tooltip: Option<lsp_types::MarkupContent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub padding_left: bool,
+    #[serde(default)]
+    pub padding_right: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text_edits: Option<Vec<lsp_types::TextEdit>>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct InlayHintLabelPart {
+    pub value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<lsp_types::Location>,
 }
 
 pub enum Ssr {}
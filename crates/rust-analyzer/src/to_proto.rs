@@ -8,8 +8,9 @@ use base_db::{FileId, FileRange};
 use ide::{
     Assist, AssistKind, CallInfo, CompletionItem, CompletionItemKind, Documentation,
     FileSystemEdit, Fold, FoldKind, Highlight, HighlightModifier, HighlightTag, HighlightedRange,
-    Indel, InlayHint, InlayKind, InsertTextFormat, LineIndex, Markup, NavigationTarget,
-    ReferenceAccess, ResolvedAssist, Runnable, Severity, SourceChange, SourceFileEdit, TextEdit,
+    Indel, InlayHint, InlayHintLabelPart, InlayKind, InsertTextFormat, LineIndex, Markup,
+    NavigationTarget, ReferenceAccess, ResolvedAssist, Runnable, Severity, SourceChange,
+    SourceFileEdit, TextEdit,
 };
 use itertools::Itertools;
 use syntax::{SyntaxKind, TextRange, TextSize};
@@ -300,15 +301,63 @@ pub(crate) fn signature_help(
     }
 }
 
-pub(crate) fn inlay_hint(line_index: &LineIndex, inlay_hint: InlayHint) -> lsp_ext::InlayHint {
+pub(crate) fn inlay_hint(
+    snap: &GlobalStateSnapshot,
+    line_index: &LineIndex,
+    line_endings: LineEndings,
+    inlay_hint: InlayHint,
+) -> lsp_ext::InlayHint {
+    let label = inlay_hint.label.to_string();
+    let label_parts = if inlay_hint.label.parts.iter().any(|part| part.goto.is_some()) {
+        let parts = inlay_hint.label.parts.into_iter();
+        Some(parts.map(|part| inlay_hint_label_part(snap, part)).collect())
+    } else {
+        None
+    };
     lsp_ext::InlayHint {
-        label: inlay_hint.label.to_string(),
+        label,
+        label_parts,
         range: range(line_index, inlay_hint.range),
+        protocol_kind: inlay_hint_kind(inlay_hint.kind),
         kind: match inlay_hint.kind {
             InlayKind::ParameterHint => lsp_ext::InlayKind::ParameterHint,
             InlayKind::TypeHint => lsp_ext::InlayKind::TypeHint,
             InlayKind::ChainingHint => lsp_ext::InlayKind::ChainingHint,
+            InlayKind::SurpriseInferenceHint => lsp_ext::InlayKind::SurpriseInferenceHint,
+            InlayKind::AdjustmentHint => lsp_ext::InlayKind::AdjustmentHint,
+            InlayKind::ArgOrderMismatchHint => lsp_ext::InlayKind::ArgOrderMismatchHint,
+            InlayKind::ClosureCaptureHint => lsp_ext::InlayKind::ClosureCaptureHint,
         },
+        tooltip: inlay_hint.tooltip.map(markup_content),
+        id: inlay_hint.id.map(|id| id.to_string()),
+        padding_left: inlay_hint.pad_left,
+        padding_right: inlay_hint.pad_right,
+        text_edits: inlay_hint.text_edit.map(|it| text_edit_vec(line_index, line_endings, it)),
+    }
+}
+
+/// The closest LSP 3.17 kind of `kind`. Chaining hints show types too, while
+/// adjustments and captures are neither a type nor a parameter.
+pub(crate) fn inlay_hint_kind(kind: InlayKind) -> Option<lsp_ext::InlayHintKind> {
+    match kind {
+        InlayKind::TypeHint | InlayKind::ChainingHint | InlayKind::SurpriseInferenceHint => {
+            Some(lsp_ext::InlayHintKind::TYPE)
+        }
+        InlayKind::ParameterHint | InlayKind::ArgOrderMismatchHint => {
+            Some(lsp_ext::InlayHintKind::PARAMETER)
+        }
+        InlayKind::AdjustmentHint | InlayKind::ClosureCaptureHint => None,
+    }
+}
+
+fn inlay_hint_label_part(
+    snap: &GlobalStateSnapshot,
+    part: InlayHintLabelPart,
+) -> lsp_ext::InlayHintLabelPart {
+    lsp_ext::InlayHintLabelPart {
+        value: part.text,
+        // A part is still worth showing if its target can't be converted.
+        location: part.goto.and_then(|frange| location(snap, frange).ok()),
     }
 }
 
@@ -807,6 +856,22 @@ fn main() {
         }
     }
 
+    #[test]
+    fn conv_inlay_hint_kinds() {
+        let kinds = [
+            (InlayKind::TypeHint, Some(lsp_ext::InlayHintKind::TYPE)),
+            (InlayKind::ParameterHint, Some(lsp_ext::InlayHintKind::PARAMETER)),
+            (InlayKind::ChainingHint, Some(lsp_ext::InlayHintKind::TYPE)),
+            (InlayKind::SurpriseInferenceHint, Some(lsp_ext::InlayHintKind::TYPE)),
+            (InlayKind::AdjustmentHint, None),
+            (InlayKind::ArgOrderMismatchHint, Some(lsp_ext::InlayHintKind::PARAMETER)),
+            (InlayKind::ClosureCaptureHint, None),
+        ];
+        for (kind, expected) in kinds.iter() {
+            assert_eq!(inlay_hint_kind(*kind), *expected, "{:?}", kind);
+        }
+    }
+
     // `Url` is not able to parse windows paths on unix machines.
     #[test]
     #[cfg(target_os = "windows")]
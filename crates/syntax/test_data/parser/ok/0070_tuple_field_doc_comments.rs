@@ -0,0 +1,6 @@
+struct S(
+    /// The first field.
+    u8,
+    // A plain comment.
+    pub(crate) String,
+);
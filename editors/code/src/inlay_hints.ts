@@ -63,6 +63,7 @@ function createHintStyle(hintKind: "type" | "parameter" | "chaining") {
         toDecoration(hint: ra.InlayHint, conv: lc.Protocol2CodeConverter): vscode.DecorationOptions {
             return {
                 range: conv.asRange(hint.range),
+                hoverMessage: hint.tooltip && new vscode.MarkdownString(hint.tooltip.value),
                 renderOptions: { [pos]: { contentText: render(hint.label) } }
             };
         }
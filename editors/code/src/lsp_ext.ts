@@ -88,13 +88,21 @@ export namespace InlayHint {
     interface Common {
         range: lc.Range;
         label: string;
+        labelParts?: InlayHintLabelPart[];
+        tooltip?: lc.MarkupContent;
+        id?: string;
     }
     export type TypeHint = Common & { kind: Kind.TypeHint };
     export type ParamHint = Common & { kind: Kind.ParamHint };
     export type ChainingHint = Common & { kind: Kind.ChainingHint };
 }
+export interface InlayHintLabelPart {
+    value: string;
+    location?: lc.Location;
+}
 export interface InlayHintsParams {
     textDocument: lc.TextDocumentIdentifier;
+    range?: lc.Range;
 }
 export const inlayHints = new lc.RequestType<InlayHintsParams, InlayHint[], void>("rust-analyzer/inlayHints");
 